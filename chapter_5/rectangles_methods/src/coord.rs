@@ -0,0 +1,137 @@
+// The number types a Rectangle can be measured in.
+//
+// Whole numbers suit pixel grids, floating point suits measurements like
+// metres on a floor plan. Each type also names a wider type for areas,
+// perimeters and far edges, so multiplying two sides or adding a side to a
+// position can't overflow.
+
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+pub trait Coord: Copy + PartialOrd + Debug + Add<Output = Self> + Sub<Output = Self> {
+    // Wide enough for the product of any two values.
+    type Wide: Copy
+        + PartialOrd
+        + Debug
+        + Add<Output = Self::Wide>
+        + Sub<Output = Self::Wide>
+        + Mul<Output = Self::Wide>;
+
+    const ZERO: Self;
+
+    fn widen(self) -> Self::Wide;
+    // Back from the wider type, None if the value doesn't fit.
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+    // The product as the type itself, None if it doesn't fit.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn to_f64(self) -> f64;
+    // The nearest value of the type, None if there is none (out of range or NaN).
+    fn from_f64(value: f64) -> Option<Self>;
+    // The value of a whole number type exactly, which f64 can't hold above
+    // 2^53. None for the floating point types.
+    fn to_i128(self) -> Option<i128>;
+    // The same value, or for floats the nearest one. None if it doesn't fit.
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! integer_coord {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Coord for $t {
+                type Wide = $wide;
+
+                const ZERO: $t = 0;
+
+                fn widen(self) -> $wide {
+                    <$wide>::from(self)
+                }
+
+                fn narrow(wide: $wide) -> Option<$t> {
+                    <$t>::try_from(wide).ok()
+                }
+
+                fn checked_mul(self, other: $t) -> Option<$t> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Option<$t> {
+                    let value = value.round();
+                    // MAX as f64 rounds up to a power of two for i64, so
+                    // adding one still gives the first value that's too big.
+                    if value >= <$t>::MIN as f64 && value < <$t>::MAX as f64 + 1.0 {
+                        Some(value as $t)
+                    } else {
+                        None
+                    }
+                }
+
+                fn to_i128(self) -> Option<i128> {
+                    Some(i128::from(self))
+                }
+
+                fn from_i128(value: i128) -> Option<$t> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                type Wide = f64;
+
+                const ZERO: $t = 0.0;
+
+                fn widen(self) -> f64 {
+                    f64::from(self)
+                }
+
+                fn narrow(wide: f64) -> Option<$t> {
+                    <$t as Coord>::from_f64(wide)
+                }
+
+                fn checked_mul(self, other: $t) -> Option<$t> {
+                    let product = self * other;
+                    if product.is_finite() {
+                        Some(product)
+                    } else {
+                        None
+                    }
+                }
+
+                fn to_f64(self) -> f64 {
+                    f64::from(self)
+                }
+
+                fn from_f64(value: f64) -> Option<$t> {
+                    let converted = value as $t;
+                    if value.is_nan() || (converted.is_infinite() && value.is_finite()) {
+                        None
+                    } else {
+                        Some(converted)
+                    }
+                }
+
+                fn to_i128(self) -> Option<i128> {
+                    None
+                }
+
+                fn from_i128(value: i128) -> Option<$t> {
+                    // Every whole number type here is well within range.
+                    Some(value as $t)
+                }
+            }
+        )*
+    };
+}
+
+integer_coord!(u32 => u64, i64 => i128);
+float_coord!(f32, f64);
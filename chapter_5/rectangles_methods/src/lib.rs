@@ -0,0 +1,11 @@
+// The Rectangle from this chapter grown into a small geometry library.
+// main.rs still walks through the chapter with its own little Rectangles;
+// this is the one meant for other code, for layout and hit-testing.
+
+pub mod coord;
+pub mod packing;
+pub mod rectangle;
+
+pub use coord::Coord;
+pub use packing::{Heuristic, Packer, Packing, Placement};
+pub use rectangle::Rectangle;
@@ -40,6 +40,18 @@ fn main() {
             "The area of the rectangle is {} square pixels.",
             rect1.area()
         );
+
+        // width * height is a u32 too, so anything bigger than 65536 by 65536
+        // overflows it: a panic in debug builds and a wrong area in release.
+        // The library's Rectangle::area (src/rectangle.rs) multiplies in a u64
+        // instead, and checked_area gives a u32 back only when it fits.
+        {
+            use rectangles_methods::Rectangle;
+
+            let big: Rectangle = Rectangle::new(0, 0, 100_000, 100_000);
+            println!("The area of the big rectangle is {}.", big.area());
+            println!("As a u32: {:?}", big.checked_area());
+        }
     }
 
     // Methods can take ownership of self, borrow self immutably as we've done here,
@@ -91,6 +103,23 @@ fn main() {
 
         println!("Can rect1 hold rect2? {}", rect1.can_hold(&rect2));
         println!("Can rect1 hold rect3? {}", rect1.can_hold(&rect3));
+
+        // Because of the strict > a rectangle can't hold another one of the
+        // same size, and rect2 never gets turned sideways to fit.
+        // The library's Rectangle (src/rectangle.rs) has can_hold with >=,
+        // can_hold_strict for the version above, and can_hold_rotated which
+        // tries both ways round.
+        {
+            use rectangles_methods::Rectangle;
+
+            let rect1: Rectangle = Rectangle::new(0, 0, 30, 50);
+            let rect4: Rectangle = Rectangle::new(0, 0, 50, 30);
+            println!("Can rect1 hold itself? {}", rect1.can_hold(&rect1));
+            println!(
+                "Can rect1 hold rect4 turned around? {}",
+                rect1.can_hold_rotated(&rect4)
+            );
+        }
     }
 
     /* Associated Functions */
@@ -148,6 +177,109 @@ fn main() {
             }
         }
     }
+    /* Positioned Rectangles */
+    // The library next to this file (src/lib.rs) has a Rectangle that also knows
+    // where it is: x and y are its top-left corner. With a position we can ask
+    // geometric questions that a size alone can't answer.
+    {
+        use rectangles_methods::Rectangle;
+
+        // Rectangle is generic over its number type (see src/coord.rs) and
+        // defaults to u32 pixels. The default is only used where a type is
+        // written out, so the literals below would be taken as i32 without
+        // the `: Rectangle` annotations.
+        let window: Rectangle = Rectangle::new(0, 0, 800, 600);
+        let button: Rectangle = Rectangle::new(700, 550, 80, 30);
+        let dialog: Rectangle = Rectangle::new(200, 150, 400, 300);
+
+        // Hit-testing: did a click at (710, 560) land on the button?
+        println!("Clicked the button? {}", button.contains_point(710, 560));
+
+        // Containment looks at where the rectangles are, not just their size.
+        println!(
+            "Is the dialog inside the window? {}",
+            window.contains(&dialog)
+        );
+
+        // The part of the dialog covering the button, if any.
+        println!("Overlap: {:?}", dialog.intersection(&button));
+
+        // The smallest rectangle around both.
+        println!("Bounding box: {:?}", dialog.union(&button));
+
+        // The area is a u64, so even the largest rectangle doesn't overflow.
+        // checked_area says whether it would have fit in a u32.
+        let huge = Rectangle::new(0, 0, u32::MAX, u32::MAX);
+        println!("Area: {}", huge.area());
+        println!("As a u32: {:?}", huge.checked_area());
+        println!(
+            "Perimeter {}, diagonal {:.1}, aspect ratio {:?}",
+            window.perimeter(),
+            window.diagonal(),
+            window.aspect_ratio()
+        );
+    }
+
+    /* Other Number Types */
+    // The same Rectangle works with floating point numbers, say a floor plan
+    // in metres, and cast turns it into another type, rounding to whole pixels.
+    {
+        use rectangles_methods::Rectangle;
+
+        let kitchen: Rectangle<f64> = Rectangle::new(0.0, 0.0, 4.2, 3.5);
+        println!("The kitchen is {:.2} square metres.", kitchen.area());
+
+        // 50 pixels to the metre.
+        let scaled = Rectangle::new(0.0, 0.0, kitchen.width * 50.0, kitchen.height * 50.0);
+        let pixels: Option<Rectangle<u32>> = scaled.cast();
+        println!("On screen: {:?}", pixels);
+
+        // Conversions that can't lose anything are plain From impls.
+        let wide: Rectangle<i64> = Rectangle::square(10u32).into();
+        println!("As i64: {:?}", wide);
+    }
+
+    /* Packing */
+    // A Packer fits a pile of rectangles into bins of one size, for example
+    // sprites into 64x64 texture atlases, and says where each one went.
+    {
+        use rectangles_methods::{Heuristic, Packer, Rectangle};
+
+        let sprites = [
+            Rectangle::new(0, 0, 32, 32),
+            Rectangle::new(0, 0, 48, 16),
+            Rectangle::new(0, 0, 16, 40),
+            Rectangle::new(0, 0, 24, 24),
+            Rectangle::new(0, 0, 16, 16),
+            Rectangle::new(0, 0, 40, 8),
+            Rectangle::new(0, 0, 8, 8),
+            Rectangle::new(0, 0, 100, 10),
+        ];
+
+        for &heuristic in &[Heuristic::Shelf, Heuristic::Guillotine, Heuristic::MaxRects] {
+            let packing = Packer::new(64, 64, heuristic).pack(&sprites);
+            println!(
+                "{:?}: {} bin(s), {:.0}% used, {} left over",
+                heuristic,
+                packing.bins,
+                packing.total_utilisation() * 100.0,
+                packing.unplaced.len()
+            );
+        }
+
+        // Where each sprite went with MaxRects.
+        let packing = Packer::new(64, 64, Heuristic::MaxRects).pack(&sprites);
+        for placement in &packing.placements {
+            println!(
+                "Sprite {} in bin {} at ({}, {}){}",
+                placement.index,
+                placement.bin,
+                placement.rectangle.x,
+                placement.rectangle.y,
+                if placement.rotated { ", rotated" } else { "" }
+            );
+        }
+    }
 }
 
 /* Summary */
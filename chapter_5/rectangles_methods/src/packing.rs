@@ -0,0 +1,575 @@
+// Packing rectangles into bins of one fixed size, like sprites into texture
+// atlases or parts into sheets of metal.
+// Only the width and height of the rectangles to pack matter; the packer
+// decides where each one goes, in which bin and whether it is turned.
+//
+// Unlike Rectangle, the packer only works in u32, whole units like pixels or
+// grid cells. The heuristics sort by size, subtract one side from another and
+// check whether one free piece lies inside another, which all need exact
+// arithmetic: with f64, a piece 0.3 wide with a rectangle 0.1 wide in it has
+// less than 0.2 left, and rounding leaves slivers no rectangle fits. Signed types
+// would need negative sizes turned down at every step. Measurements in other
+// types pack fine once scaled to whole units, millimetres instead of metres.
+
+use crate::rectangle::Rectangle;
+use std::cmp::Reverse;
+
+// How the free space in a bin is kept track of and filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    // Rows of rectangles side by side, each row as tall as the first one in
+    // it. Quick and simple, but leaves gaps above the shorter ones.
+    Shelf,
+    // Every rectangle is cut off a free piece with cuts going all the way
+    // across, the only kind a guillotine or panel saw can make.
+    Guillotine,
+    // Keeps every largest free rectangle, even overlapping ones, so it finds
+    // room the others miss. Usually the tightest, and the slowest.
+    MaxRects,
+}
+
+// Where one of the rectangles ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    // Its position in the list given to pack.
+    pub index: usize,
+    pub bin: usize,
+    // Where it is in the bin, with width and height already swapped if it
+    // was rotated.
+    pub rectangle: Rectangle,
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packing {
+    // The size of every bin, at the origin.
+    pub bin: Rectangle,
+    pub bins: usize,
+    // One for every rectangle that was packed, in the order they were given.
+    pub placements: Vec<Placement>,
+    // The indices of the rectangles that couldn't be packed: empty ones and
+    // ones bigger than a bin.
+    pub unplaced: Vec<usize>,
+}
+
+impl Packing {
+    // The area covered by rectangles in one bin.
+    pub fn used_area(&self, bin: usize) -> u64 {
+        self.placements
+            .iter()
+            .filter(|placement| placement.bin == bin)
+            .map(|placement| placement.rectangle.area())
+            .sum()
+    }
+
+    // How much of one bin is covered, from 0.0 to 1.0.
+    pub fn utilisation(&self, bin: usize) -> f64 {
+        ratio(u128::from(self.used_area(bin)), u128::from(self.bin.area()))
+    }
+
+    // How much of all the bins together is covered. Added up in u128, since
+    // a few bins near u32::MAX on a side already cover more than a u64 holds.
+    pub fn total_utilisation(&self) -> f64 {
+        let used = self
+            .placements
+            .iter()
+            .map(|placement| u128::from(placement.rectangle.area()))
+            .sum();
+        ratio(used, u128::from(self.bin.area()) * self.bins as u128)
+    }
+}
+
+fn ratio(used: u128, total: u128) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packer {
+    pub width: u32,
+    pub height: u32,
+    pub heuristic: Heuristic,
+    // Whether rectangles may be turned a quarter to fit better. True unless
+    // changed, but a sprite sheet whose loader can't rotate might not want it.
+    pub allow_rotation: bool,
+}
+
+impl Packer {
+    pub fn new(width: u32, height: u32, heuristic: Heuristic) -> Packer {
+        Packer {
+            width,
+            height,
+            heuristic,
+            allow_rotation: true,
+        }
+    }
+
+    // Packs the rectangles, opening a new bin whenever one doesn't fit in any
+    // of the bins so far. The biggest go first, since the small ones are
+    // easier to squeeze into what is left.
+    pub fn pack(&self, rectangles: &[Rectangle]) -> Packing {
+        let bin = Rectangle::new(0, 0, self.width, self.height);
+        let mut order: Vec<usize> = (0..rectangles.len()).collect();
+        order.sort_by_key(|&index| Reverse(self.sort_key(&rectangles[index])));
+
+        let mut spaces: Vec<Space> = Vec::new();
+        let mut placements = Vec::new();
+        let mut unplaced = Vec::new();
+
+        for index in order {
+            let wanted = rectangles[index];
+            let fits = if self.allow_rotation {
+                bin.can_hold_rotated(&wanted)
+            } else {
+                bin.can_hold(&wanted)
+            };
+            if wanted.is_empty() || !fits {
+                unplaced.push(index);
+                continue;
+            }
+
+            let found = spaces.iter_mut().enumerate().find_map(|(number, space)| {
+                space
+                    .insert(wanted.width, wanted.height, self.allow_rotation)
+                    .map(|rectangle| (number, rectangle))
+            });
+            let (number, rectangle) = match found {
+                Some(found) => found,
+                None => {
+                    let mut space = Space::new(self.heuristic, self.width, self.height);
+                    let rectangle = space
+                        .insert(wanted.width, wanted.height, self.allow_rotation)
+                        .expect("a rectangle the bin can hold fits in an empty one");
+                    spaces.push(space);
+                    (spaces.len() - 1, rectangle)
+                }
+            };
+
+            placements.push(Placement {
+                index,
+                bin: number,
+                rectangle,
+                rotated: rectangle.width != wanted.width,
+            });
+        }
+
+        placements.sort_by_key(|placement| placement.index);
+        unplaced.sort_unstable();
+
+        Packing {
+            bin,
+            bins: spaces.len(),
+            placements,
+            unplaced,
+        }
+    }
+
+    // Shelves fill up best from the tallest rectangle down, counting the
+    // height it will have when turned to lie flat. The others go by the
+    // longest side.
+    fn sort_key(&self, rectangle: &Rectangle) -> (u32, u32) {
+        let long = rectangle.width.max(rectangle.height);
+        let short = rectangle.width.min(rectangle.height);
+        match self.heuristic {
+            Heuristic::Shelf if self.allow_rotation => (short, long),
+            Heuristic::Shelf => (rectangle.height, rectangle.width),
+            _ => (long, short),
+        }
+    }
+}
+
+// The width and height to try: as given, and turned if that is allowed.
+fn orientations(width: u32, height: u32, rotation: bool) -> Vec<(u32, u32)> {
+    if rotation && width != height {
+        vec![(width, height), (height, width)]
+    } else {
+        vec![(width, height)]
+    }
+}
+
+// The far edges of a rectangle in a bin. Everything in a bin ends inside it,
+// so unlike Rectangle::right these fit in a u32.
+fn right(rectangle: &Rectangle) -> u32 {
+    rectangle.x + rectangle.width
+}
+
+fn bottom(rectangle: &Rectangle) -> u32 {
+    rectangle.y + rectangle.height
+}
+
+// The free space of one bin, for each heuristic.
+enum Space {
+    Shelf {
+        width: u32,
+        height: u32,
+        shelves: Vec<Shelf>,
+    },
+    Guillotine(Vec<Rectangle>),
+    MaxRects(Vec<Rectangle>),
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    // How far along the shelf is already taken.
+    used: u32,
+}
+
+impl Space {
+    fn new(heuristic: Heuristic, width: u32, height: u32) -> Space {
+        let whole = Rectangle::new(0, 0, width, height);
+        match heuristic {
+            Heuristic::Shelf => Space::Shelf {
+                width,
+                height,
+                shelves: Vec::new(),
+            },
+            Heuristic::Guillotine => Space::Guillotine(vec![whole]),
+            Heuristic::MaxRects => Space::MaxRects(vec![whole]),
+        }
+    }
+
+    // Finds room for a rectangle of this size and takes it, or returns None
+    // if there is none left in this bin.
+    fn insert(&mut self, width: u32, height: u32, rotation: bool) -> Option<Rectangle> {
+        let orientations = orientations(width, height, rotation);
+        match self {
+            Space::Shelf {
+                width: bin_width,
+                height: bin_height,
+                shelves,
+            } => insert_on_shelf(*bin_width, *bin_height, shelves, &orientations),
+            Space::Guillotine(free) => insert_guillotine(free, &orientations),
+            Space::MaxRects(free) => insert_max_rects(free, &orientations),
+        }
+    }
+}
+
+// Puts the rectangle on the shelf where it wastes the least height, or starts
+// a new shelf on top of the last one, lying as flat as the bin allows.
+fn insert_on_shelf(
+    bin_width: u32,
+    bin_height: u32,
+    shelves: &mut Vec<Shelf>,
+    orientations: &[(u32, u32)],
+) -> Option<Rectangle> {
+    let mut best: Option<(u32, usize, u32, u32)> = None;
+    for (number, shelf) in shelves.iter().enumerate() {
+        for &(width, height) in orientations {
+            if height <= shelf.height && width <= bin_width - shelf.used {
+                let waste = shelf.height - height;
+                if best.is_none_or(|(least, ..)| waste < least) {
+                    best = Some((waste, number, width, height));
+                }
+            }
+        }
+    }
+
+    if let Some((_, number, width, height)) = best {
+        let shelf = &mut shelves[number];
+        let rectangle = Rectangle::new(shelf.used, shelf.y, width, height);
+        shelf.used += width;
+        return Some(rectangle);
+    }
+
+    let top = shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+    let &(width, height) = orientations
+        .iter()
+        .filter(|&&(width, height)| width <= bin_width && height <= bin_height - top)
+        .min_by_key(|&&(_, height)| height)?;
+    shelves.push(Shelf {
+        y: top,
+        height,
+        used: width,
+    });
+    Some(Rectangle::new(0, top, width, height))
+}
+
+// Takes the top-left corner of the free piece the rectangle fills best,
+// then cuts what is left of that piece in two, across the shorter leftover
+// side so the bigger of the two pieces stays as large as it can.
+fn insert_guillotine(free: &mut Vec<Rectangle>, orientations: &[(u32, u32)]) -> Option<Rectangle> {
+    let mut best: Option<((u64, u32), usize, u32, u32)> = None;
+    for (number, piece) in free.iter().enumerate() {
+        for &(width, height) in orientations {
+            if piece.width >= width && piece.height >= height {
+                let left_over = piece.area() - u64::from(width) * u64::from(height);
+                let short_side = (piece.width - width).min(piece.height - height);
+                let score = (left_over, short_side);
+                if best.is_none_or(|(least, ..)| score < least) {
+                    best = Some((score, number, width, height));
+                }
+            }
+        }
+    }
+
+    let (_, number, width, height) = best?;
+    let piece = free.swap_remove(number);
+    let placed = Rectangle::new(piece.x, piece.y, width, height);
+
+    let (right, below) = if piece.width - width <= piece.height - height {
+        // Cut straight across under the rectangle first.
+        (
+            Rectangle::new(right(&placed), piece.y, piece.width - width, height),
+            Rectangle::new(piece.x, bottom(&placed), piece.width, piece.height - height),
+        )
+    } else {
+        // Cut straight down beside it first.
+        (
+            Rectangle::new(right(&placed), piece.y, piece.width - width, piece.height),
+            Rectangle::new(piece.x, bottom(&placed), width, piece.height - height),
+        )
+    };
+    free.extend([right, below].iter().filter(|piece| !piece.is_empty()));
+
+    Some(placed)
+}
+
+// Takes the free rectangle that leaves the smallest gap along one side
+// (best short side fit), then shrinks every free rectangle the new one
+// overlaps and drops any that end up inside another.
+fn insert_max_rects(free: &mut Vec<Rectangle>, orientations: &[(u32, u32)]) -> Option<Rectangle> {
+    let mut best: Option<((u32, u32), Rectangle)> = None;
+    for piece in free.iter() {
+        for &(width, height) in orientations {
+            if piece.width >= width && piece.height >= height {
+                let gap_x = piece.width - width;
+                let gap_y = piece.height - height;
+                let score = (gap_x.min(gap_y), gap_x.max(gap_y));
+                if best.is_none_or(|(least, _)| score < least) {
+                    best = Some((score, Rectangle::new(piece.x, piece.y, width, height)));
+                }
+            }
+        }
+    }
+
+    let (_, placed) = best?;
+    let mut pieces = Vec::new();
+    for piece in free.iter() {
+        if !piece.intersects(&placed) {
+            pieces.push(*piece);
+            continue;
+        }
+
+        // What is left of the piece on each side of the placed rectangle,
+        // each as big as it can be, so they overlap at the corners.
+        if placed.x > piece.x {
+            pieces.push(Rectangle::new(
+                piece.x,
+                piece.y,
+                placed.x - piece.x,
+                piece.height,
+            ));
+        }
+        if right(&placed) < right(piece) {
+            let width = right(piece) - right(&placed);
+            pieces.push(Rectangle::new(right(&placed), piece.y, width, piece.height));
+        }
+        if placed.y > piece.y {
+            pieces.push(Rectangle::new(
+                piece.x,
+                piece.y,
+                piece.width,
+                placed.y - piece.y,
+            ));
+        }
+        if bottom(&placed) < bottom(piece) {
+            let height = bottom(piece) - bottom(&placed);
+            pieces.push(Rectangle::new(
+                piece.x,
+                bottom(&placed),
+                piece.width,
+                height,
+            ));
+        }
+    }
+
+    // Keep the first of any that are the same.
+    *free = pieces
+        .iter()
+        .enumerate()
+        .filter(|&(number, piece)| {
+            !pieces.iter().enumerate().any(|(other_number, other)| {
+                other_number != number
+                    && other.contains(piece)
+                    && (other != piece || other_number < number)
+            })
+        })
+        .map(|(_, piece)| *piece)
+        .collect();
+
+    Some(placed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const HEURISTICS: [Heuristic; 3] =
+        [Heuristic::Shelf, Heuristic::Guillotine, Heuristic::MaxRects];
+
+    fn size(width: u32, height: u32) -> Rectangle {
+        Rectangle::new(0, 0, width, height)
+    }
+
+    // Every packer, with and without rotation, for a bin of this size.
+    fn packers(width: u32, height: u32) -> Vec<Packer> {
+        let mut packers = Vec::new();
+        for &heuristic in HEURISTICS.iter() {
+            for &allow_rotation in [true, false].iter() {
+                packers.push(Packer {
+                    allow_rotation,
+                    ..Packer::new(width, height, heuristic)
+                });
+            }
+        }
+        packers
+    }
+
+    // Checks everything a packing promises about the rectangles it was given.
+    fn check(packer: &Packer, rectangles: &[Rectangle], packing: &Packing) {
+        let bin = size(packer.width, packer.height);
+        assert_eq!(packing.bin, bin);
+
+        let mut seen = vec![0; rectangles.len()];
+        for placement in &packing.placements {
+            seen[placement.index] += 1;
+            let wanted = rectangles[placement.index];
+            let placed = placement.rectangle;
+
+            assert!(bin.contains(&placed), "{:?} is outside the bin", placement);
+            assert!(placement.bin < packing.bins);
+            if placement.rotated {
+                assert!(packer.allow_rotation, "{:?} was turned", placement);
+                assert_ne!(wanted.width, wanted.height, "a square was turned");
+                assert_eq!((placed.width, placed.height), (wanted.height, wanted.width));
+            } else {
+                assert_eq!((placed.width, placed.height), (wanted.width, wanted.height));
+            }
+        }
+        for &index in &packing.unplaced {
+            seen[index] += 1;
+            let wanted = rectangles[index];
+            let fits = if packer.allow_rotation {
+                bin.can_hold_rotated(&wanted)
+            } else {
+                bin.can_hold(&wanted)
+            };
+            assert!(
+                wanted.is_empty() || !fits,
+                "{:?} could have been packed",
+                wanted
+            );
+        }
+        assert!(seen.iter().all(|&count| count == 1), "{:?}", seen);
+
+        for (number, a) in packing.placements.iter().enumerate() {
+            for b in &packing.placements[number + 1..] {
+                assert!(
+                    a.bin != b.bin || !a.rectangle.intersects(&b.rectangle),
+                    "{:?} and {:?} overlap",
+                    a,
+                    b
+                );
+            }
+        }
+
+        for number in 0..packing.bins {
+            assert!(packing
+                .placements
+                .iter()
+                .any(|placement| placement.bin == number));
+            let utilisation = packing.utilisation(number);
+            assert!(utilisation > 0.0 && utilisation <= 1.0);
+        }
+        let total = packing.total_utilisation();
+        assert!((0.0..=1.0).contains(&total));
+    }
+
+    #[test]
+    fn empty_and_oversized_rectangles_are_left_out() {
+        let rectangles = [
+            size(10, 10),
+            size(0, 5),
+            size(5, 0),
+            size(101, 1),
+            size(1, 51),
+            size(50, 100),
+            size(100, 50),
+        ];
+        for packer in packers(100, 50) {
+            let packing = packer.pack(&rectangles);
+            check(&packer, &rectangles, &packing);
+            if packer.allow_rotation {
+                // 1x51 and 50x100 fit once they're turned.
+                assert_eq!(packing.unplaced, [1, 2, 3], "{:?}", packer);
+            } else {
+                assert_eq!(packing.unplaced, [1, 2, 3, 4, 5], "{:?}", packer);
+            }
+        }
+    }
+
+    #[test]
+    fn a_tall_rectangle_is_turned_to_fit_a_wide_bin() {
+        for packer in packers(40, 10) {
+            let packing = packer.pack(&[size(10, 40)]);
+            check(&packer, &[size(10, 40)], &packing);
+            if packer.allow_rotation {
+                assert_eq!(packing.placements.len(), 1);
+                assert!(packing.placements[0].rotated);
+                assert_eq!(packing.placements[0].rectangle, size(40, 10));
+                assert_eq!(packing.total_utilisation(), 1.0);
+            } else {
+                assert_eq!(packing.unplaced, [0]);
+                assert_eq!(packing.bins, 0);
+                assert_eq!(packing.total_utilisation(), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn a_perfect_fit_fills_one_bin() {
+        let quarters = [size(50, 25), size(50, 25), size(50, 25), size(50, 25)];
+        for packer in packers(100, 50) {
+            let packing = packer.pack(&quarters);
+            check(&packer, &quarters, &packing);
+            assert_eq!(packing.bins, 1, "{:?}", packer);
+            assert_eq!(packing.utilisation(0), 1.0);
+        }
+    }
+
+    #[test]
+    fn huge_bins_dont_overflow_the_utilisation() {
+        let whole = size(u32::MAX, u32::MAX);
+        let rectangles = [whole, whole, whole, size(u32::MAX, 1)];
+        for packer in packers(u32::MAX, u32::MAX) {
+            let packing = packer.pack(&rectangles);
+            check(&packer, &rectangles, &packing);
+            assert_eq!(packing.bins, 4);
+            assert_eq!(packing.used_area(0), whole.area());
+            assert_eq!(packing.utilisation(0), 1.0);
+            assert!((packing.total_utilisation() - 0.75).abs() < 1e-9);
+        }
+    }
+
+    fn rectangles() -> impl Strategy<Value = Vec<Rectangle>> {
+        // Mostly ones that fit, some empty and some too big for the bin.
+        let side = prop_oneof![8 => 1..=40u32, 1 => Just(0u32), 1 => 41..=90u32];
+        prop::collection::vec((side.clone(), side), 0..40)
+            .prop_map(|sizes| sizes.into_iter().map(|(w, h)| size(w, h)).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn every_packing_keeps_its_promises(rectangles in rectangles()) {
+            for packer in packers(64, 40) {
+                let packing = packer.pack(&rectangles);
+                check(&packer, &rectangles, &packing);
+            }
+        }
+    }
+}
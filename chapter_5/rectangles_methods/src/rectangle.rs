@@ -0,0 +1,513 @@
+use crate::coord::Coord;
+
+// A rectangle with a position as well as a size, measured in any Coord type:
+// u32 pixels by default, or i64, f32 or f64.
+// (x, y) is the top-left corner and y grows downwards, like pixels on a screen.
+// A rectangle covers the points from x up to but not including x + width,
+// and the same for y, so two rectangles that only share an edge don't overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rectangle<T = u32> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: Coord> Rectangle<T> {
+    pub fn new(x: T, y: T, width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    // A square at the origin, like the associated function in main.rs.
+    pub fn square(size: T) -> Rectangle<T> {
+        Rectangle::new(T::ZERO, T::ZERO, size, size)
+    }
+
+    // The area in the wider type (u64 for u32 sides), which the product of
+    // any two sides fits in, so big rectangles can't overflow the way
+    // width * height on u32 would.
+    pub fn area(&self) -> T::Wide {
+        self.width.widen() * self.height.widen()
+    }
+
+    // The area in the rectangle's own type, or None if it doesn't fit in one.
+    pub fn checked_area(&self) -> Option<T> {
+        self.width.checked_mul(self.height)
+    }
+
+    // All the way around: 2 * (width + height), again in the wider type.
+    pub fn perimeter(&self) -> T::Wide {
+        let half = self.width.widen() + self.height.widen();
+        half + half
+    }
+
+    // The distance from one corner to the opposite one.
+    pub fn diagonal(&self) -> f64 {
+        self.width.to_f64().hypot(self.height.to_f64())
+    }
+
+    // Width divided by height, like 16/9 = 1.78 for a widescreen monitor.
+    // None for a rectangle with no height.
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        if self.height == T::ZERO {
+            None
+        } else {
+            Some(self.width.to_f64() / self.height.to_f64())
+        }
+    }
+
+    // Size-based only: could other fit inside self if it were moved there?
+    // A rectangle of the same size fits exactly, so it can be held.
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+
+    // Like can_hold, but other has to be smaller on both sides, leaving a gap
+    // all around. This is what can_hold in main.rs does.
+    pub fn can_hold_strict(&self, other: &Rectangle<T>) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    // Like can_hold, but other may also be turned a quarter so its width and
+    // height swap, the way a 10x40 box fits into a 40x10 one.
+    pub fn can_hold_rotated(&self, other: &Rectangle<T>) -> bool {
+        self.can_hold(other) || self.can_hold(&other.rotated())
+    }
+
+    // The same rectangle turned a quarter around its top-left corner, with
+    // width and height swapped.
+    pub fn rotated(&self) -> Rectangle<T> {
+        Rectangle::new(self.x, self.y, self.height, self.width)
+    }
+
+    // The first x and y past the rectangle, in the wider type like the area,
+    // since a rectangle near the largest position can reach past it.
+    pub fn right(&self) -> T::Wide {
+        self.x.widen() + self.width.widen()
+    }
+
+    pub fn bottom(&self) -> T::Wide {
+        self.y.widen() + self.height.widen()
+    }
+
+    // A rectangle with no width or no height covers no points at all.
+    // Neither does one with a negative side.
+    pub fn is_empty(&self) -> bool {
+        !(self.width > T::ZERO && self.height > T::ZERO)
+    }
+
+    // Whether the point (x, y) is inside, for hit-testing.
+    pub fn contains_point(&self, x: T, y: T) -> bool {
+        x >= self.x && x.widen() < self.right() && y >= self.y && y.widen() < self.bottom()
+    }
+
+    // Whether other lies completely within self, where it is now.
+    // Unlike can_hold this looks at positions, and other may touch the edges.
+    pub fn contains(&self, other: &Rectangle<T>) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    // Whether the two share any area. Touching edges don't count.
+    pub fn intersects(&self, other: &Rectangle<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The area both rectangles cover, or None if they don't overlap.
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        let x = max(self.x, other.x);
+        let y = max(self.y, other.y);
+        let right = min(self.right(), other.right());
+        let bottom = min(self.bottom(), other.bottom());
+
+        if x.widen() < right && y.widen() < bottom {
+            // No wider or taller than either of them, so it fits.
+            Some(Rectangle::new(
+                x,
+                y,
+                T::narrow(right - x.widen())?,
+                T::narrow(bottom - y.widen())?,
+            ))
+        } else {
+            None
+        }
+    }
+
+    // The smallest rectangle covering both, also called their bounding box.
+    // It can cover points neither of them does. None if it is too big for
+    // the type, like the box around rectangles at 0 and near u32::MAX.
+    pub fn union(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        let x = min(self.x, other.x);
+        let y = min(self.y, other.y);
+        let right = max(self.right(), other.right());
+        let bottom = max(self.bottom(), other.bottom());
+        Some(Rectangle::new(
+            x,
+            y,
+            T::narrow(right - x.widen())?,
+            T::narrow(bottom - y.widen())?,
+        ))
+    }
+
+    // The bounding box of any number of rectangles, None if there are none
+    // or it is too big for the type.
+    pub fn bounding_box<'a, I>(rectangles: I) -> Option<Rectangle<T>>
+    where
+        I: IntoIterator<Item = &'a Rectangle<T>>,
+        T: 'a,
+    {
+        let mut rectangles = rectangles.into_iter();
+        let first = *rectangles.next()?;
+        rectangles.try_fold(first, |bounds, rectangle| bounds.union(rectangle))
+    }
+
+    // The same rectangle in another type, None if a value doesn't fit.
+    // Whole numbers stay exact between whole number types. Floats round to
+    // the nearest whole number, so a floor plan in metres can be snapped to a
+    // pixel grid, and whole numbers become the nearest float, which above
+    // 2^24 for f32 or 2^53 for f64 can be a neighbouring number.
+    pub fn cast<U: Coord>(&self) -> Option<Rectangle<U>> {
+        Some(Rectangle::new(
+            convert(self.x)?,
+            convert(self.y)?,
+            convert(self.width)?,
+            convert(self.height)?,
+        ))
+    }
+}
+
+fn convert<T: Coord, U: Coord>(value: T) -> Option<U> {
+    match value.to_i128() {
+        Some(whole) => U::from_i128(whole),
+        None => U::from_f64(value.to_f64()),
+    }
+}
+
+// Ord::min and max don't work for floats, which are only PartialOrd.
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+// Conversions that never lose anything are plain From impls,
+// the rest go through cast.
+macro_rules! lossless_from {
+    ($($from:ty => $to:ty),*) => {
+        $(
+            impl From<Rectangle<$from>> for Rectangle<$to> {
+                fn from(rectangle: Rectangle<$from>) -> Rectangle<$to> {
+                    Rectangle::new(
+                        <$to>::from(rectangle.x),
+                        <$to>::from(rectangle.y),
+                        <$to>::from(rectangle.width),
+                        <$to>::from(rectangle.height),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+lossless_from!(u32 => i64, u32 => f64, f32 => f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Plain integer literals would be i32, which isn't a Coord.
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(x, y, width, height)
+    }
+
+    #[test]
+    fn overlapping_rectangles_intersect() {
+        let a = rect(0, 0, 10, 10);
+        let b = rect(5, 5, 10, 10);
+        assert_eq!(a.intersection(&b), Some(rect(5, 5, 5, 5)));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+        assert!(a.intersects(&b));
+
+        // Sharing an edge or a corner isn't overlapping.
+        assert!(!a.intersects(&rect(10, 0, 5, 5)));
+        assert!(!a.intersects(&rect(10, 10, 5, 5)));
+        // Nor is an empty rectangle inside another one.
+        assert!(!a.intersects(&rect(5, 5, 0, 3)));
+    }
+
+    #[test]
+    fn containment_includes_the_edges() {
+        let a = rect(10, 20, 30, 40);
+        assert!(a.contains(&a));
+        assert!(a.contains(&rect(10, 20, 1, 1)));
+        assert!(a.contains(&rect(39, 59, 1, 1)));
+        assert!(!a.contains(&rect(39, 59, 2, 1)));
+        assert!(!a.contains(&rect(9, 20, 1, 1)));
+
+        assert!(a.contains_point(10, 20));
+        assert!(a.contains_point(39, 59));
+        assert!(!a.contains_point(40, 59));
+        assert!(!a.contains_point(39, 60));
+    }
+
+    #[test]
+    fn the_union_covers_both() {
+        let a = rect(0, 0, 10, 10);
+        let b = rect(20, 5, 10, 10);
+        let union = a.union(&b).unwrap();
+        assert_eq!(union, rect(0, 0, 30, 15));
+        assert!(union.contains(&a) && union.contains(&b));
+
+        let c = rect(5, 30, 1, 1);
+        assert_eq!(
+            Rectangle::bounding_box(&[a, b, c]),
+            Some(rect(0, 0, 30, 31))
+        );
+        assert_eq!(Rectangle::<u32>::bounding_box(&[]), None);
+    }
+
+    #[test]
+    fn rectangles_at_the_edge_of_u32_dont_overflow() {
+        let a = rect(u32::MAX - 5, 0, 10, 10);
+        let b = rect(u32::MAX - 3, 0, 1, 1);
+        assert_eq!(a.right(), u64::from(u32::MAX) + 5);
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(b));
+        assert!(a.contains(&b));
+        assert!(a.contains_point(u32::MAX, 9));
+        assert!(!b.contains(&a));
+
+        // A rectangle from u32::MAX reaching almost to 2^33.
+        let c = rect(u32::MAX, 5, u32::MAX, u32::MAX);
+        assert_eq!(a.intersection(&c), Some(rect(u32::MAX, 5, 5, 5)));
+        assert_eq!(c.intersection(&c), Some(c));
+
+        // The box from 0 to past u32::MAX is too wide for a u32.
+        let origin = rect(0, 0, 1, 1);
+        assert_eq!(origin.union(&a), None);
+        assert_eq!(Rectangle::bounding_box(&[origin, a]), None);
+        assert_eq!(origin.union(&b), Some(rect(0, 0, u32::MAX - 2, 1)));
+    }
+
+    #[test]
+    fn the_area_of_the_biggest_rectangle_fits_in_u64() {
+        let biggest = rect(0, 0, u32::MAX, u32::MAX);
+        assert_eq!(biggest.area(), u64::from(u32::MAX) * u64::from(u32::MAX));
+        assert_eq!(biggest.area(), 18_446_744_065_119_617_025);
+        assert_eq!(biggest.checked_area(), None);
+        assert_eq!(rect(0, 0, 0, u32::MAX).area(), 0);
+    }
+
+    #[test]
+    fn checked_area_is_some_up_to_u32_max() {
+        // 65,535 * 65,537 = 2^32 - 1 exactly.
+        assert_eq!(rect(0, 0, 65_535, 65_537).checked_area(), Some(u32::MAX));
+        assert_eq!(rect(0, 0, 65_536, 65_536).checked_area(), None);
+        assert_eq!(rect(0, 0, u32::MAX, 1).checked_area(), Some(u32::MAX));
+        assert_eq!(rect(0, 0, u32::MAX, 0).checked_area(), Some(0));
+        assert_eq!(rect(0, 0, 30, 50).checked_area(), Some(1500));
+    }
+
+    #[test]
+    fn the_perimeter_of_the_biggest_rectangle_fits_in_u64() {
+        assert_eq!(
+            rect(0, 0, u32::MAX, u32::MAX).perimeter(),
+            4 * u64::from(u32::MAX)
+        );
+        assert_eq!(rect(0, 0, 30, 50).perimeter(), 160);
+        assert_eq!(rect(0, 0, 0, 0).perimeter(), 0);
+    }
+
+    #[test]
+    fn diagonal_and_aspect_ratio_at_the_edges() {
+        assert_eq!(rect(0, 0, 3, 4).diagonal(), 5.0);
+        let max = f64::from(u32::MAX);
+        assert_eq!(rect(0, 0, u32::MAX, 0).diagonal(), max);
+        assert!((rect(0, 0, u32::MAX, u32::MAX).diagonal() - max * 2f64.sqrt()).abs() < 1.0);
+
+        assert_eq!(rect(0, 0, 1920, 1080).aspect_ratio(), Some(16.0 / 9.0));
+        assert_eq!(rect(0, 0, u32::MAX, 1).aspect_ratio(), Some(max));
+        assert_eq!(rect(0, 0, 1, u32::MAX).aspect_ratio(), Some(1.0 / max));
+        assert_eq!(rect(0, 0, u32::MAX, u32::MAX).aspect_ratio(), Some(1.0));
+        assert_eq!(rect(0, 0, 5, 0).aspect_ratio(), None);
+        assert_eq!(rect(0, 0, 0, 5).aspect_ratio(), Some(0.0));
+    }
+
+    #[test]
+    fn equal_sizes_fit_unless_strict() {
+        let a = rect(0, 0, 30, 50);
+        assert!(a.can_hold(&rect(100, 100, 30, 50)));
+        assert!(!a.can_hold_strict(&rect(100, 100, 30, 50)));
+        assert!(a.can_hold_strict(&rect(0, 0, 29, 49)));
+
+        // Nothing fits into an empty rectangle except another empty one.
+        let empty = rect(0, 0, 0, 0);
+        assert!(empty.can_hold(&empty));
+        assert!(!empty.can_hold(&rect(0, 0, 1, 0)));
+        assert!(!empty.can_hold_strict(&empty));
+    }
+
+    #[test]
+    fn turning_makes_a_tall_box_fit_a_wide_one() {
+        let wide = rect(0, 0, 40, 10);
+        let tall = rect(0, 0, 10, 40);
+        assert!(!wide.can_hold(&tall));
+        assert!(wide.can_hold_rotated(&tall));
+        assert!(tall.can_hold_rotated(&wide));
+        assert!(!wide.can_hold_rotated(&rect(0, 0, 11, 40)));
+
+        let huge = rect(0, 0, u32::MAX, 1);
+        assert!(huge.can_hold_rotated(&rect(0, 0, 1, u32::MAX)));
+        assert!(!huge.can_hold_rotated(&rect(0, 0, 2, u32::MAX)));
+    }
+
+    // Sides that are small, at the edges of u32 or anything in between.
+    fn side() -> impl Strategy<Value = u32> {
+        prop_oneof![0..=64u32, Just(u32::MAX), Just(u32::MAX - 1), any::<u32>()]
+    }
+
+    fn any_rectangle() -> impl Strategy<Value = Rectangle> {
+        (any::<u32>(), any::<u32>(), side(), side())
+            .prop_map(|(x, y, width, height)| rect(x, y, width, height))
+    }
+
+    proptest! {
+        #[test]
+        fn can_hold_compares_the_sides(a in any_rectangle(), b in any_rectangle()) {
+            prop_assert_eq!(a.can_hold(&b), a.width >= b.width && a.height >= b.height);
+            prop_assert_eq!(a.can_hold_strict(&b), a.width > b.width && a.height > b.height);
+        }
+
+        #[test]
+        fn where_they_are_doesnt_matter(a in any_rectangle(), b in any_rectangle(), x: u32, y: u32) {
+            let moved = Rectangle::new(x, y, b.width, b.height);
+            prop_assert_eq!(a.can_hold(&b), a.can_hold(&moved));
+            prop_assert_eq!(a.can_hold_strict(&b), a.can_hold_strict(&moved));
+            prop_assert_eq!(a.can_hold_rotated(&b), a.can_hold_rotated(&moved));
+        }
+
+        #[test]
+        fn a_rectangle_holds_itself_but_not_strictly(a in any_rectangle()) {
+            prop_assert!(a.can_hold(&a));
+            prop_assert!(a.can_hold_rotated(&a));
+            prop_assert!(!a.can_hold_strict(&a));
+        }
+
+        #[test]
+        fn each_variant_is_looser_than_the_last(a in any_rectangle(), b in any_rectangle()) {
+            if a.can_hold_strict(&b) {
+                prop_assert!(a.can_hold(&b));
+            }
+            if a.can_hold(&b) {
+                prop_assert!(a.can_hold_rotated(&b));
+            }
+        }
+
+        #[test]
+        fn rotated_tries_both_ways(a in any_rectangle(), b in any_rectangle()) {
+            prop_assert_eq!(
+                a.can_hold_rotated(&b),
+                a.can_hold(&b) || a.can_hold(&b.rotated())
+            );
+            // Turning either of them, or both, changes nothing.
+            prop_assert_eq!(a.can_hold_rotated(&b), a.can_hold_rotated(&b.rotated()));
+            prop_assert_eq!(a.can_hold_rotated(&b), a.rotated().can_hold_rotated(&b));
+        }
+
+        #[test]
+        fn holding_is_transitive(a in any_rectangle(), b in any_rectangle(), c in any_rectangle()) {
+            if a.can_hold(&b) && b.can_hold(&c) {
+                prop_assert!(a.can_hold(&c));
+            }
+            if a.can_hold_rotated(&b) && b.can_hold_rotated(&c) {
+                prop_assert!(a.can_hold_rotated(&c));
+            }
+        }
+
+        #[test]
+        fn what_it_can_hold_fits_inside_it(a in any_rectangle(), b in any_rectangle()) {
+            // Moved to the same corner, a rectangle it can hold is contained.
+            let moved = Rectangle::new(a.x, a.y, b.width, b.height);
+            prop_assert_eq!(a.can_hold(&b), a.contains(&moved));
+        }
+    }
+
+    #[test]
+    fn whole_numbers_cast_exactly() {
+        let past_f64 = Rectangle::new((1i64 << 53) + 1, 0, 1, 1);
+        assert_eq!(past_f64.cast::<i64>(), Some(past_f64));
+
+        let biggest = Rectangle::new(i64::MAX, i64::MIN, i64::MAX, 1);
+        assert_eq!(biggest.cast::<i64>(), Some(biggest));
+        assert_eq!(biggest.cast::<u32>(), None);
+
+        let pixels = rect(u32::MAX, 0, u32::MAX - 1, 7);
+        assert_eq!(pixels.cast::<i64>(), Some(Rectangle::from(pixels)));
+        assert_eq!(
+            Rectangle::new(i64::from(u32::MAX), 0, 1, 1).cast::<u32>(),
+            Some(rect(u32::MAX, 0, 1, 1))
+        );
+        assert_eq!(
+            Rectangle::new(i64::from(u32::MAX) + 1, 0, 1, 1).cast::<u32>(),
+            None
+        );
+        assert_eq!(Rectangle::new(-1i64, 0, 1, 1).cast::<u32>(), None);
+    }
+
+    #[test]
+    fn floats_round_to_the_nearest_whole_number() {
+        let metres = Rectangle::new(0.4f64, 1.5, 2.6, -0.4);
+        assert_eq!(metres.cast::<i64>(), Some(Rectangle::new(0, 2, 3, 0)));
+        assert_eq!(metres.cast::<u32>(), Some(rect(0, 2, 3, 0)));
+        assert_eq!(Rectangle::new(-0.6f64, 0.0, 1.0, 1.0).cast::<u32>(), None);
+        assert_eq!(Rectangle::new(f64::NAN, 0.0, 1.0, 1.0).cast::<i64>(), None);
+        assert_eq!(Rectangle::new(1e300, 0.0, 1.0, 1.0).cast::<f32>(), None);
+        assert_eq!(
+            Rectangle::new(0.5f32, 1.0, 2.0, 3.0).cast::<f64>(),
+            Some(Rectangle::from(Rectangle::new(0.5f32, 1.0, 2.0, 3.0)))
+        );
+
+        // Into floats whole numbers take the nearest float.
+        assert_eq!(
+            rect(1, 2, 3, u32::MAX).cast::<f64>(),
+            Some(Rectangle::new(1.0, 2.0, 3.0, f64::from(u32::MAX)))
+        );
+        assert_eq!(
+            Rectangle::new(i64::MAX, 0, 0, 0).cast::<f64>(),
+            Some(Rectangle::new(9_223_372_036_854_775_808.0, 0.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn negative_positions_work_with_i64() {
+        let a = Rectangle::new(-10i64, -10, 20, 20);
+        let b = Rectangle::new(i64::MAX - 1, 0, i64::MAX, 1);
+        assert!(a.contains_point(-10, 9));
+        assert!(!a.contains_point(10, 0));
+        assert_eq!(
+            a.intersection(&Rectangle::new(-20, -20, 15, 15)),
+            Some(Rectangle::new(-10, -10, 5, 5))
+        );
+        assert!(b.contains_point(i64::MAX, 0));
+        assert!(!a.intersects(&b));
+        assert_eq!(a.union(&b), None);
+    }
+}
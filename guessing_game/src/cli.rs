@@ -0,0 +1,1091 @@
+use guessing_game::clock::TimeLimits;
+use guessing_game::date::Date;
+use guessing_game::domain::Dates;
+use guessing_game::mastermind::{MAX_BENCH_DIGITS, MAX_DIGITS, MIN_DIGITS};
+use guessing_game::rating::DEFAULT_RATING_FILE;
+use guessing_game::scores::DEFAULT_SCORE_FILE;
+use guessing_game::solver::STRATEGIES;
+use guessing_game::{Difficulty, HintBands, Lang, Settings};
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: guessing_game [OPTIONS]
+       guessing_game scores [--difficulty <NAME>] [--score-file <PATH>]
+       guessing_game bench [--games <N>] [--seed <N>] [ROUND OPTIONS]
+       guessing_game reverse [ROUND OPTIONS]
+       guessing_game hotseat --players <A,B,...> [--shared] [--best-of <N>]
+                             [--seed <N>] [ROUND OPTIONS]
+       guessing_game serve [--host <ADDR>] [--port <N>] [--seed <N>]
+                           [ROUND OPTIONS]
+       guessing_game json [--seed <N>] [ROUND OPTIONS]
+       guessing_game replay <TRANSCRIPT>
+       guessing_game stats [--difficulty <NAME>] [--player <NAME>] [--json]
+                           [--score-file <PATH>]
+       guessing_game daily [--date <DATE>] [--difficulty <NAME>]
+       guessing_game mastermind [--digits <N>] [--max-guesses <N> | --unlimited]
+                                [--seed <N>] [--show-seed] [--auto | --bench]
+
+Round options:
+  -d, --difficulty <NAME>  easy, normal, hard or custom (default: normal)
+      --min <N>            lowest possible secret number (custom)
+      --max <N>            highest possible secret number (custom)
+      --max-guesses <N>    number of guesses before the round is lost (custom)
+      --unlimited          never run out of guesses (custom)
+      --count-invalid      invalid or repeated guesses use up a guess too
+      --hints              give burning/warm/cold hints after wrong guesses
+      --hint-bands <B,W>   hint when within B (burning) or W (warm) of the secret
+
+Options:
+      --seed <N>           pick the secret number from a fixed seed
+      --show-seed          print the seed when the round is over
+      --name <NAME>        player name for the high-score table (default: $USER)
+      --score-file <PATH>  where scores are kept (default: guessing_game_scores.json)
+      --adaptive           pick the range and guess limit from your level, which
+                           goes up as you keep winning and down as you lose
+      --rating-file <PATH>
+                           where levels are kept (default: guessing_game_ratings.json)
+      --record <PATH>      save a transcript of the round to PATH
+      --tui                play full screen instead of line by line
+      --lang <CODE>        en or de (default: from LC_ALL, LC_MESSAGES or LANG)
+      --time-limit <SECS>  lose the round when it takes longer than SECS
+      --guess-time <SECS>  lose the round when a guess takes longer than SECS
+      --secret <KIND>      number, word, date or color (default: number)
+      --words <PATH>       dictionary for word secrets, one word per line
+      --from <DATE>        earliest date secret, YYYY-MM-DD (default: 2000-01-01)
+      --to <DATE>          latest date secret, YYYY-MM-DD (default: 2029-12-31)
+      --auto               let the binary search bot play instead of you
+      --strategy <NAME>    let a bot play: binary, random or linear
+  -h, --help               print this help
+
+Every command also takes --lang <CODE> and speaks that language, or the
+locale's. Errors, warnings and this help are in English.
+
+Presets:
+  easy    1-50,   10 guesses
+  normal  1-100,  7 guesses
+  hard    1-1000, 10 guesses
+
+Any of --min, --max, --max-guesses or --unlimited switches to a custom round
+that starts from the selected preset.
+
+Word, date and color secrets get as many guesses as it takes to be sure of
+finding them, plus the spare guesses of the preset (4 on easy, none on normal
+and hard), unless --max-guesses or --unlimited says otherwise. --min, --max,
+--count-invalid, --hints and --hint-bands are for number secrets only.
+
+bench plays --games rounds (default: 1000) with every bot strategy and reports
+the mean and maximum number of guesses each needed.
+
+reverse swaps roles: think of a number in the round's range and answer the
+computer's guesses with h (higher), l (lower) or c (correct).
+
+hotseat lets two or more players take turns at the same keyboard. Each player
+chases their own secret, or with --shared the same one, and the round goes to
+whoever finds it in the fewest guesses. A match is best of --best-of rounds
+(default: 3).
+
+serve hosts a game per TCP connection on --host (default: 127.0.0.1) and
+--port (default: 7878). Clients send GUESS <n>, NEW or QUIT, one per line, and
+get TOO_SMALL, TOO_BIG, WIN <guesses>, LOSE <secret> or ERROR <reason> back.
+
+json plays on stdin and stdout with one JSON object per line, for programs
+rather than people. Requests are new_game, guess, status and quit, e.g.
+{\"type\": \"guess\", \"value\": 50}. The round options are the defaults for
+new_game.
+
+replay plays the guesses of a transcript saved with --record again and checks
+the game still answers every one of them the same way.
+
+stats sums up every round in the score file: win rate, mean and median
+guesses, how often each guess count came up, and how each player compares to
+the guesses a binary search is guaranteed to need.
+
+daily plays the challenge of the day (UTC), or of --date, which is the same
+for everybody playing it. Only the presets can be used, normal by default.
+Once it is over, a result to share is printed.
+
+mastermind hides a code of --digits different digits (default: 4) instead of
+a number. Every guess is answered with bulls, right digits in the right place,
+and cows, right digits in the wrong place. You have --max-guesses guesses
+(default: 10). --auto lets the solver crack it, and --bench plays the solver
+against every possible code and reports how many guesses it needed. That takes
+about a second for 4 digits and half a minute for 5, the most it works with.";
+
+pub enum Command {
+    Play(Options),
+    Scores(ScoresOptions),
+    Bench(BenchOptions),
+    Reverse(ReverseOptions),
+    Hotseat(HotseatOptions),
+    Serve(ServeOptions),
+    Json(JsonOptions),
+    Replay(ReplayOptions),
+    Stats(StatsOptions),
+    Daily(DailyOptions),
+    Mastermind(MastermindOptions),
+    Help,
+}
+
+pub struct Options {
+    pub settings: Settings,
+    pub seed: Option<u64>,
+    pub show_seed: bool,
+    pub player: String,
+    pub score_file: PathBuf,
+    // The bot strategy playing the round, if it isn't a person.
+    pub strategy: Option<String>,
+    pub record: Option<PathBuf>,
+    pub tui: bool,
+    pub secret: SecretKind,
+    // Whether --max-guesses or --unlimited was given, so that words, dates and
+    // colors keep that limit instead of one sized to fit them.
+    pub limit_given: bool,
+    pub time_limits: TimeLimits,
+    pub lang: Lang,
+    // Take the round from the player's level instead of the round options.
+    pub adaptive: bool,
+    pub rating_file: PathBuf,
+}
+
+// What kind of secret a round is played over. Only numbers use the round's
+// range; the others size their guess limit to fit, unless one is given.
+pub enum SecretKind {
+    Number,
+    // Words from the built-in dictionary, or from the given file.
+    Word(Option<PathBuf>),
+    Date(Dates),
+    Color,
+}
+
+pub struct ScoresOptions {
+    // Only show this difficulty's table instead of all of them.
+    pub difficulty: Option<Difficulty>,
+    pub score_file: PathBuf,
+    pub lang: Lang,
+}
+
+pub struct StatsOptions {
+    pub difficulty: Option<Difficulty>,
+    pub player: Option<String>,
+    pub json: bool,
+    pub score_file: PathBuf,
+    pub lang: Lang,
+}
+
+pub struct DailyOptions {
+    pub date: Date,
+    pub difficulty: Difficulty,
+    pub lang: Lang,
+}
+
+pub struct MastermindOptions {
+    pub digits: usize,
+    pub max_guesses: Option<u32>,
+    pub seed: Option<u64>,
+    pub show_seed: bool,
+    pub auto: bool,
+    // Measure the solver instead of playing a round.
+    pub bench: bool,
+    pub lang: Lang,
+}
+
+pub struct BenchOptions {
+    pub settings: Settings,
+    pub games: u32,
+    pub seed: Option<u64>,
+    pub lang: Lang,
+}
+
+pub struct ReverseOptions {
+    pub settings: Settings,
+    pub lang: Lang,
+}
+
+pub struct HotseatOptions {
+    pub settings: Settings,
+    pub players: Vec<String>,
+    pub shared: bool,
+    pub best_of: u32,
+    pub seed: Option<u64>,
+    pub lang: Lang,
+}
+
+pub struct JsonOptions {
+    pub settings: Settings,
+    pub seed: Option<u64>,
+}
+
+pub struct ServeOptions {
+    pub settings: Settings,
+    pub host: String,
+    pub port: u16,
+    pub seed: Option<u64>,
+    pub lang: Lang,
+}
+
+pub struct ReplayOptions {
+    pub path: PathBuf,
+    pub lang: Lang,
+}
+
+pub fn parse<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("scores") => {
+            args.next();
+            return parse_scores(args);
+        }
+        Some("bench") => {
+            args.next();
+            return parse_bench(args);
+        }
+        Some("reverse") => {
+            args.next();
+            return parse_reverse(args);
+        }
+        Some("hotseat") => {
+            args.next();
+            return parse_hotseat(args);
+        }
+        Some("serve") => {
+            args.next();
+            return parse_serve(args);
+        }
+        Some("json") => {
+            args.next();
+            return parse_json(args);
+        }
+        Some("replay") => {
+            args.next();
+            return parse_replay(args);
+        }
+        Some("stats") => {
+            args.next();
+            return parse_stats(args);
+        }
+        Some("daily") => {
+            args.next();
+            return parse_daily(args);
+        }
+        Some("mastermind") => {
+            args.next();
+            return parse_mastermind(args);
+        }
+        _ => {}
+    }
+
+    let mut flags = SettingsFlags::default();
+    let mut seed = None;
+    let mut show_seed = false;
+    let mut player = None;
+    let mut score_file = None;
+    let mut strategy = None;
+    let mut record = None;
+    let mut tui = false;
+    let mut secret = String::from("number");
+    let mut words = None;
+    let mut dates = Dates::default();
+    let mut time_limits = TimeLimits::default();
+    let mut lang = None;
+    let mut adaptive = false;
+    let mut rating_file = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            "--show-seed" => show_seed = true,
+            "--name" => player = Some(value(&arg, args.next())?),
+            "--score-file" => score_file = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--adaptive" => adaptive = true,
+            "--rating-file" => rating_file = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--tui" => tui = true,
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            "--secret" => secret = value(&arg, args.next())?,
+            "--words" => words = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--from" => dates.first = date(&arg, args.next())?,
+            "--to" => dates.last = date(&arg, args.next())?,
+            "--record" => record = Some(PathBuf::from(value(&arg, args.next())?)),
+            "--time-limit" => time_limits.round = Some(seconds(&arg, args.next())?),
+            "--guess-time" => time_limits.guess = Some(seconds(&arg, args.next())?),
+            "--auto" => strategy = Some(String::from("binary")),
+            "--strategy" => {
+                let name = value(&arg, args.next())?;
+                if !STRATEGIES.contains(&name.as_str()) {
+                    return Err(format!("unknown strategy '{}'", name));
+                }
+                strategy = Some(name);
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if tui && (strategy.is_some() || record.is_some() || time_limits.is_set()) {
+        return Err(String::from(
+            "--tui cannot be combined with --auto, --strategy, --record, --time-limit or --guess-time",
+        ));
+    }
+
+    if adaptive && flags.sets_round() {
+        return Err(String::from(
+            "--adaptive picks the round itself, leave out --difficulty, --min, --max, --max-guesses and --unlimited",
+        ));
+    }
+
+    let secret = match secret.as_str() {
+        "number" => SecretKind::Number,
+        "word" => SecretKind::Word(words),
+        "date" if dates.first > dates.last => {
+            return Err(format!(
+                "--from {} is after --to {}",
+                dates.first, dates.last
+            ))
+        }
+        "date" => SecretKind::Date(dates),
+        "color" => SecretKind::Color,
+        _ => return Err(format!("unknown kind of secret '{}'", secret)),
+    };
+    if !matches!(secret, SecretKind::Number)
+        && (tui || strategy.is_some() || record.is_some() || time_limits.is_set() || adaptive)
+    {
+        return Err(String::from(
+            "--tui, --auto, --strategy, --record, --time-limit, --guess-time and --adaptive only work with number secrets",
+        ));
+    }
+    if !matches!(secret, SecretKind::Number) && flags.sets_number_rules() {
+        return Err(String::from(
+            "--min, --max, --count-invalid, --hints and --hint-bands only work with number secrets",
+        ));
+    }
+
+    let limit_given = flags.sets_limit();
+    Ok(Command::Play(Options {
+        settings: flags.build()?,
+        seed,
+        show_seed,
+        player: player.unwrap_or_else(default_player),
+        score_file: score_file.unwrap_or_else(|| PathBuf::from(DEFAULT_SCORE_FILE)),
+        strategy,
+        record,
+        tui,
+        secret,
+        limit_given,
+        time_limits,
+        lang: lang.unwrap_or_else(Lang::from_env),
+        adaptive,
+        rating_file: rating_file.unwrap_or_else(|| PathBuf::from(DEFAULT_RATING_FILE)),
+    }))
+}
+
+fn parse_scores<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut difficulty = None;
+    let mut score_file = PathBuf::from(DEFAULT_SCORE_FILE);
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-d" | "--difficulty" => difficulty = Some(self::difficulty(&arg, args.next())?),
+            "--score-file" => score_file = PathBuf::from(value(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Scores(ScoresOptions {
+        difficulty,
+        score_file,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_stats<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut difficulty = None;
+    let mut player = None;
+    let mut json = false;
+    let mut score_file = PathBuf::from(DEFAULT_SCORE_FILE);
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-d" | "--difficulty" => difficulty = Some(self::difficulty(&arg, args.next())?),
+            "--player" => player = Some(value(&arg, args.next())?),
+            "--json" => json = true,
+            "--score-file" => score_file = PathBuf::from(value(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Stats(StatsOptions {
+        difficulty,
+        player,
+        json,
+        score_file,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_bench<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut flags = SettingsFlags::default();
+    let mut games = 1000;
+    let mut seed = None;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--games" => games = number(&arg, args.next())?,
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Bench(BenchOptions {
+        settings: flags.build()?,
+        games,
+        seed,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_reverse<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut flags = SettingsFlags::default();
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Reverse(ReverseOptions {
+        settings: flags.build()?,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_hotseat<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut flags = SettingsFlags::default();
+    let mut players = Vec::new();
+    let mut shared = false;
+    let mut best_of = 3;
+    let mut seed = None;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--players" => {
+                players = value(&arg, args.next())?
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            }
+            "--shared" => shared = true,
+            "--best-of" => best_of = number(&arg, args.next())?,
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if players.len() < 2 {
+        return Err(String::from(
+            "hotseat needs at least two players, e.g. --players alice,bob",
+        ));
+    }
+    if best_of == 0 {
+        return Err(String::from("--best-of must be at least 1"));
+    }
+
+    Ok(Command::Hotseat(HotseatOptions {
+        settings: flags.build()?,
+        players,
+        shared,
+        best_of,
+        seed,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_serve<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut flags = SettingsFlags::default();
+    let mut host = String::from("127.0.0.1");
+    let mut port = 7878;
+    let mut seed = None;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--host" => host = value(&arg, args.next())?,
+            "--port" => port = number(&arg, args.next())?,
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Serve(ServeOptions {
+        settings: flags.build()?,
+        host,
+        port,
+        seed,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_json<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut flags = SettingsFlags::default();
+    let mut seed = None;
+
+    while let Some(arg) = args.next() {
+        if flags.accept(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Json(JsonOptions {
+        settings: flags.build()?,
+        seed,
+    }))
+}
+
+fn parse_replay<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut path = None;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let path = path.ok_or_else(|| String::from("replay needs a transcript file"))?;
+    Ok(Command::Replay(ReplayOptions {
+        path,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_daily<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut date = None;
+    let mut difficulty = Difficulty::Normal;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--date" => date = Some(self::date(&arg, args.next())?),
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            "-d" | "--difficulty" => difficulty = self::difficulty(&arg, args.next())?,
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if difficulty == Difficulty::Custom {
+        return Err(String::from(
+            "the daily challenge is played on easy, normal or hard",
+        ));
+    }
+
+    Ok(Command::Daily(DailyOptions {
+        date: date.unwrap_or_else(Date::today),
+        difficulty,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+fn parse_mastermind<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = args;
+    let mut digits = 4;
+    let mut max_guesses = Some(10);
+    let mut seed = None;
+    let mut show_seed = false;
+    let mut auto = false;
+    let mut bench = false;
+    let mut lang = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--digits" => digits = number(&arg, args.next())?,
+            "--max-guesses" => max_guesses = Some(number(&arg, args.next())?),
+            "--unlimited" => max_guesses = None,
+            "--seed" => seed = Some(number(&arg, args.next())?),
+            "--show-seed" => show_seed = true,
+            "--auto" => auto = true,
+            "--bench" => bench = true,
+            "--lang" => lang = Some(self::lang(&arg, args.next())?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&digits) {
+        return Err(format!(
+            "--digits must be between {} and {}",
+            MIN_DIGITS, MAX_DIGITS
+        ));
+    }
+    if max_guesses == Some(0) {
+        return Err(String::from("--max-guesses must be at least 1"));
+    }
+    if auto && bench {
+        return Err(String::from("--auto cannot be combined with --bench"));
+    }
+    if bench && digits > MAX_BENCH_DIGITS {
+        return Err(format!(
+            "--bench works with up to {} digits",
+            MAX_BENCH_DIGITS
+        ));
+    }
+
+    Ok(Command::Mastermind(MastermindOptions {
+        digits,
+        max_guesses,
+        seed,
+        show_seed,
+        auto,
+        bench,
+        lang: lang.unwrap_or_else(Lang::from_env),
+    }))
+}
+
+// The round options, shared by every command that plays rounds.
+#[derive(Default)]
+struct SettingsFlags {
+    difficulty: Option<Difficulty>,
+    low: Option<u32>,
+    high: Option<u32>,
+    max_guesses: Option<u32>,
+    unlimited: bool,
+    count_invalid: bool,
+    hints: bool,
+    hint_bands: Option<HintBands>,
+}
+
+impl SettingsFlags {
+    // Takes the flag (and its value) if it is a round option.
+    // Returns whether it was one.
+    fn accept<I>(&mut self, arg: &str, args: &mut I) -> Result<bool, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        match arg {
+            "-d" | "--difficulty" => self.difficulty = Some(difficulty(arg, args.next())?),
+            "--min" => self.low = Some(number(arg, args.next())?),
+            "--max" => self.high = Some(number(arg, args.next())?),
+            "--max-guesses" => self.max_guesses = Some(number(arg, args.next())?),
+            "--unlimited" => self.unlimited = true,
+            "--count-invalid" => self.count_invalid = true,
+            "--hints" => self.hints = true,
+            "--hint-bands" => self.hint_bands = Some(hint_bands(arg, args.next())?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn sets_limit(&self) -> bool {
+        self.max_guesses.is_some() || self.unlimited
+    }
+
+    // Whether any option that picks the range or the guess limit was given.
+    fn sets_round(&self) -> bool {
+        self.difficulty.is_some()
+            || self.low.is_some()
+            || self.high.is_some()
+            || self.max_guesses.is_some()
+            || self.unlimited
+    }
+
+    // Whether any option that only means something for number secrets was
+    // given. The other secrets have their own range and hints, and never
+    // count invalid guesses.
+    fn sets_number_rules(&self) -> bool {
+        self.low.is_some()
+            || self.high.is_some()
+            || self.count_invalid
+            || self.hints
+            || self.hint_bands.is_some()
+    }
+
+    fn build(self) -> Result<Settings, String> {
+        let difficulty = self.difficulty.unwrap_or(Difficulty::Normal);
+        let preset = Settings::preset(difficulty);
+        let customized = self.low.is_some()
+            || self.high.is_some()
+            || self.max_guesses.is_some()
+            || self.unlimited;
+
+        let mut settings = if difficulty == Difficulty::Custom || customized {
+            if self.unlimited && self.max_guesses.is_some() {
+                return Err(String::from(
+                    "--unlimited cannot be combined with --max-guesses",
+                ));
+            }
+            let max_guesses = if self.unlimited {
+                None
+            } else {
+                self.max_guesses.or(preset.max_guesses)
+            };
+            Settings::custom(
+                self.low.unwrap_or(preset.low),
+                self.high.unwrap_or(preset.high),
+                max_guesses,
+            )?
+        } else {
+            preset
+        };
+        settings.count_invalid = self.count_invalid;
+        if self.hints || self.hint_bands.is_some() {
+            settings.hints = Some(
+                self.hint_bands
+                    .unwrap_or_else(|| settings.default_hint_bands()),
+            );
+        }
+
+        Ok(settings)
+    }
+}
+
+// The login name is a good enough default for a local leaderboard.
+fn default_player() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| String::from("anonymous"))
+}
+
+fn difficulty(flag: &str, value: Option<String>) -> Result<Difficulty, String> {
+    let name = self::value(flag, value)?;
+    Difficulty::from_name(&name).ok_or_else(|| format!("unknown difficulty '{}'", name))
+}
+
+fn hint_bands(flag: &str, value: Option<String>) -> Result<HintBands, String> {
+    let value = self::value(flag, value)?;
+    let bands: Vec<u32> = value
+        .split(',')
+        .map(|band| band.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("{} expects two whole numbers, got '{}'", flag, value))?;
+
+    match bands[..] {
+        [burning, warm] if burning < warm => Ok(HintBands { burning, warm }),
+        [_, _] => Err(format!(
+            "{}: the burning band must be below the warm one",
+            flag
+        )),
+        _ => Err(format!(
+            "{} expects two whole numbers, got '{}'",
+            flag, value
+        )),
+    }
+}
+
+fn lang(flag: &str, value: Option<String>) -> Result<Lang, String> {
+    let code = self::value(flag, value)?;
+    Lang::from_code(&code).ok_or_else(|| {
+        let known: Vec<&str> = Lang::ALL.iter().map(|lang| lang.code()).collect();
+        format!(
+            "unknown language '{}', pick one of {}",
+            code,
+            known.join(", ")
+        )
+    })
+}
+
+fn date(flag: &str, value: Option<String>) -> Result<Date, String> {
+    let value = self::value(flag, value)?;
+    value.parse().map_err(|e| format!("{}: {}", flag, e))
+}
+
+fn seconds(flag: &str, value: Option<String>) -> Result<Duration, String> {
+    match number(flag, value)? {
+        0 => Err(format!("{} must be at least 1 second", flag)),
+        seconds => Ok(Duration::from_secs(seconds)),
+    }
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = self::value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a whole number, got '{}'", flag, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    // Feeds the arguments to the round options alone.
+    fn flags(args: &[&str]) -> Result<Settings, String> {
+        let mut flags = SettingsFlags::default();
+        let mut args = args.iter().map(|arg| arg.to_string());
+        while let Some(arg) = args.next() {
+            if !flags.accept(&arg, &mut args)? {
+                return Err(format!("not a round option: {}", arg));
+            }
+        }
+        flags.build()
+    }
+
+    // The settings of a round played with these arguments.
+    fn settings(args: &[&str]) -> Result<Settings, String> {
+        match parse_args(args)? {
+            Command::Play(options) => Ok(options.settings),
+            _ => panic!("{:?} doesn't play a round", args),
+        }
+    }
+
+    #[test]
+    fn no_options_is_a_normal_round() {
+        assert_eq!(flags(&[]), Ok(Settings::preset(Difficulty::Normal)));
+        assert_eq!(
+            flags(&["-d", "hard"]),
+            Ok(Settings::preset(Difficulty::Hard))
+        );
+        assert_eq!(
+            flags(&["--difficulty", "easy"]),
+            Ok(Settings::preset(Difficulty::Easy))
+        );
+    }
+
+    #[test]
+    fn the_range_and_limit_switch_to_custom_from_the_preset() {
+        let settings = flags(&["-d", "hard", "--min", "500"]).unwrap();
+        assert_eq!(settings.difficulty, Difficulty::Custom);
+        assert_eq!(
+            (settings.low, settings.high, settings.max_guesses),
+            (500, 1000, Some(10))
+        );
+
+        let settings = flags(&["--max", "20"]).unwrap();
+        assert_eq!(
+            (settings.low, settings.high, settings.max_guesses),
+            (1, 20, Some(7))
+        );
+
+        let settings = flags(&["-d", "easy", "--max-guesses", "3"]).unwrap();
+        assert_eq!(
+            (settings.low, settings.high, settings.max_guesses),
+            (1, 50, Some(3))
+        );
+
+        let settings = flags(&["--unlimited"]).unwrap();
+        assert_eq!(settings.difficulty, Difficulty::Custom);
+        assert_eq!(settings.max_guesses, None);
+
+        // Custom on its own starts out like normal.
+        let settings = flags(&["-d", "custom"]).unwrap();
+        assert_eq!(settings.difficulty, Difficulty::Custom);
+        assert_eq!(
+            (settings.low, settings.high, settings.max_guesses),
+            (1, 100, Some(7))
+        );
+    }
+
+    #[test]
+    fn a_limit_and_no_limit_at_once_is_an_error() {
+        assert!(flags(&["--unlimited", "--max-guesses", "5"]).is_err());
+        assert!(flags(&["--max-guesses", "5", "--unlimited"]).is_err());
+    }
+
+    #[test]
+    fn a_range_no_round_can_be_played_with_is_an_error() {
+        assert!(flags(&["--min", "50", "--max", "10"]).is_err());
+        assert!(flags(&["--max-guesses", "0"]).is_err());
+        assert!(flags(&["--max", "4294967295"]).is_err());
+    }
+
+    #[test]
+    fn hints_get_the_difficultys_bands_unless_given() {
+        let settings = flags(&["--hints"]).unwrap();
+        assert_eq!(settings.hints, Some(settings.default_hint_bands()));
+
+        let bands = HintBands {
+            burning: 2,
+            warm: 9,
+        };
+        assert_eq!(flags(&["--hint-bands", "2,9"]).unwrap().hints, Some(bands));
+        assert_eq!(
+            flags(&["--hints", "--hint-bands", " 2, 9"]).unwrap().hints,
+            Some(bands)
+        );
+        assert!(flags(&["--hint-bands", "9,2"]).is_err());
+        assert!(flags(&["--hint-bands", "2"]).is_err());
+        assert!(flags(&["--hint-bands", "2,9,20"]).is_err());
+
+        assert!(flags(&["--count-invalid"]).unwrap().count_invalid);
+        assert_eq!(flags(&[]).unwrap().hints, None);
+    }
+
+    #[test]
+    fn flag_values_are_checked() {
+        assert!(flags(&["--min"]).is_err());
+        assert!(flags(&["--min", "-1"]).is_err());
+        assert!(flags(&["--max", "ten"]).is_err());
+        assert!(flags(&["-d", "impossible"]).is_err());
+        assert!(flags(&["--seed", "1"]).is_err());
+    }
+
+    #[test]
+    fn the_round_options_reach_the_round() {
+        let settings = settings(&["--seed", "3", "--max", "30", "--hints"]).unwrap();
+        assert_eq!((settings.low, settings.high), (1, 30));
+        assert!(settings.hints.is_some());
+        assert!(parse_args(&["--bogus"]).is_err());
+        assert!(matches!(parse_args(&["--help"]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn adaptive_rounds_pick_their_own_range_and_limit() {
+        assert!(parse_args(&["--adaptive"]).is_ok());
+        assert!(parse_args(&["--adaptive", "--hints"]).is_ok());
+        for flags in &[
+            &["-d", "easy"][..],
+            &["--min", "5"],
+            &["--max", "50"],
+            &["--max-guesses", "3"],
+            &["--unlimited"],
+        ] {
+            let mut args = vec!["--adaptive"];
+            args.extend_from_slice(flags);
+            assert!(parse_args(&args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn the_limit_given_is_remembered_for_other_secrets() {
+        for (args, given) in &[
+            (&["--secret", "word"][..], false),
+            (&["--secret", "word", "--max-guesses", "4"], true),
+            (&["--secret", "word", "--unlimited"], true),
+        ] {
+            match parse_args(args) {
+                Ok(Command::Play(options)) => assert_eq!(options.limit_given, *given),
+                _ => panic!("{:?}", args),
+            }
+        }
+    }
+
+    #[test]
+    fn subcommands_take_the_round_options_too() {
+        match parse_args(&["bench", "-d", "hard", "--games", "5"]) {
+            Ok(Command::Bench(options)) => {
+                assert_eq!(options.settings, Settings::preset(Difficulty::Hard))
+            }
+            _ => panic!("bench"),
+        }
+        assert!(parse_args(&["reverse", "--unlimited", "--max-guesses", "3"]).is_err());
+    }
+
+    #[test]
+    fn number_rules_are_rejected_for_other_secrets() {
+        for kind in &["word", "date", "color"] {
+            for flags in &[
+                &["--min", "5"][..],
+                &["--max", "50"],
+                &["--count-invalid"],
+                &["--hints"],
+                &["--hint-bands", "2,5"],
+            ] {
+                let mut args = vec!["--secret", kind];
+                args.extend_from_slice(flags);
+                assert!(parse_args(&args).is_err(), "{:?}", args);
+            }
+            // The guess limit still applies to them.
+            assert!(parse_args(&["--secret", kind, "--max-guesses", "20"]).is_ok());
+            assert!(parse_args(&["--secret", kind, "-d", "easy"]).is_ok());
+        }
+        assert!(parse_args(&["--min", "5", "--hints", "--count-invalid"]).is_ok());
+    }
+
+    #[test]
+    fn mastermind_benches_only_lengths_that_finish() {
+        assert!(parse_args(&["mastermind", "--bench", "--digits", "5"]).is_ok());
+        assert!(parse_args(&["mastermind", "--bench", "--digits", "6"]).is_err());
+        assert!(parse_args(&["mastermind", "--digits", "6"]).is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+// Countdowns for time attack rounds.
+//
+// A round can have a limit on its total time, on the time for each guess, or
+// both. The clock only keeps time; it is up to the front end to wait on input
+// no longer than deadline() and to call Game::time_out when it passes.
+
+use crate::messages::Lang;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeLimits {
+    // For the whole round.
+    pub round: Option<Duration>,
+    // For each guess, starting when the game asks for it.
+    pub guess: Option<Duration>,
+}
+
+impl TimeLimits {
+    pub fn is_set(&self) -> bool {
+        self.round.is_some() || self.guess.is_some()
+    }
+}
+
+// Which limit ran out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expired {
+    Round,
+    Guess,
+}
+
+impl fmt::Display for Expired {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.expired(*self))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    limits: TimeLimits,
+    started: Instant,
+    guess_started: Instant,
+}
+
+impl Clock {
+    pub fn start(limits: TimeLimits) -> Clock {
+        let now = Instant::now();
+        Clock {
+            limits,
+            started: now,
+            guess_started: now,
+        }
+    }
+
+    // Starts the countdown for the next guess.
+    pub fn start_guess(&mut self) {
+        self.guess_started = Instant::now();
+    }
+
+    // When the first limit that is still running out will pass, if there is one.
+    pub fn deadline(&self) -> Option<Instant> {
+        let round = self.limits.round.map(|limit| self.started + limit);
+        let guess = self.limits.guess.map(|limit| self.guess_started + limit);
+        match (round, guess) {
+            (Some(round), Some(guess)) => Some(round.min(guess)),
+            (round, guess) => round.or(guess),
+        }
+    }
+
+    // Which limit has passed, if any. The round's comes first when both have.
+    pub fn expired(&self) -> Option<Expired> {
+        let now = Instant::now();
+        if self
+            .limits
+            .round
+            .is_some_and(|limit| now >= self.started + limit)
+        {
+            Some(Expired::Round)
+        } else if self
+            .limits
+            .guess
+            .is_some_and(|limit| now >= self.guess_started + limit)
+        {
+            Some(Expired::Guess)
+        } else {
+            None
+        }
+    }
+
+    // What is left of the round's time, None without a round limit.
+    pub fn round_left(&self) -> Option<Duration> {
+        self.limits
+            .round
+            .map(|limit| limit.saturating_sub(self.started.elapsed()))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn clock(round: Option<Duration>, guess: Option<Duration>) -> Clock {
+        Clock::start(TimeLimits { round, guess })
+    }
+
+    #[test]
+    fn without_limits_nothing_runs_out() {
+        let clock = clock(None, None);
+        assert!(!clock.limits.is_set());
+        assert_eq!(clock.deadline(), None);
+        assert_eq!(clock.expired(), None);
+        assert_eq!(clock.round_left(), None);
+    }
+
+    #[test]
+    fn the_earlier_limit_is_the_deadline() {
+        let clock = self::clock(Some(HOUR), Some(SECOND));
+        assert_eq!(clock.deadline(), Some(clock.guess_started + SECOND));
+
+        let clock = self::clock(Some(SECOND), Some(HOUR));
+        assert_eq!(clock.deadline(), Some(clock.started + SECOND));
+
+        let clock = self::clock(None, Some(SECOND));
+        assert_eq!(clock.deadline(), Some(clock.guess_started + SECOND));
+    }
+
+    #[test]
+    fn the_guess_countdown_restarts_with_every_guess() {
+        let mut clock = clock(None, Some(HOUR));
+        let first = clock.deadline().unwrap();
+        clock.start_guess();
+        assert!(clock.deadline().unwrap() >= first);
+        assert_eq!(clock.deadline(), Some(clock.guess_started + HOUR));
+    }
+
+    #[test]
+    fn the_round_limit_is_reported_before_the_guess_limit() {
+        assert_eq!(
+            clock(Some(Duration::ZERO), Some(Duration::ZERO)).expired(),
+            Some(Expired::Round)
+        );
+        assert_eq!(
+            clock(Some(HOUR), Some(Duration::ZERO)).expired(),
+            Some(Expired::Guess)
+        );
+        assert_eq!(clock(Some(HOUR), Some(HOUR)).expired(), None);
+    }
+
+    #[test]
+    fn the_time_left_stops_at_zero() {
+        assert_eq!(
+            clock(Some(Duration::ZERO), None).round_left(),
+            Some(Duration::ZERO)
+        );
+        assert!(clock(Some(HOUR), None).round_left().unwrap() <= HOUR);
+    }
+}
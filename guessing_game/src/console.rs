@@ -0,0 +1,45 @@
+// The text of the command line game.
+// Kept apart from the binary so transcripts can be replayed against exactly
+// what a player would have seen.
+
+use crate::difficulty::Settings;
+use crate::game::{Feedback, Game, Status};
+use crate::messages::{Lang, Message};
+
+// The lines printed before the first guess.
+pub fn banner(lang: Lang, settings: &Settings) -> Vec<String> {
+    vec![
+        lang.text(Message::Title),
+        lang.text(Message::Difficulty {
+            difficulty: settings.difficulty,
+            low: settings.low,
+            high: settings.high,
+        }),
+    ]
+}
+
+// The lines printed in answer to one line of input, after the game has taken it.
+pub fn responses(lang: Lang, game: &Game, input: &str, feedback: Feedback) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if let Feedback::Invalid(_) = feedback {
+        lines.push(lang.feedback(&feedback));
+        if !game.settings().count_invalid {
+            return lines;
+        }
+    } else {
+        lines.push(lang.text(Message::YouGuessed(input.trim())));
+        lines.push(lang.feedback(&feedback));
+        if let Some(hint) = game.hint() {
+            lines.push(lang.hint(&hint));
+        }
+    }
+
+    if game.status() == Status::Lost {
+        lines.push(lang.text(Message::Lose(game.secret())));
+    } else if let (Status::Playing, Some(left)) = (game.status(), game.guesses_left()) {
+        lines.push(lang.text(Message::GuessesLeft(left)));
+    }
+
+    lines
+}
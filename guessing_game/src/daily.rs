@@ -0,0 +1,125 @@
+// The daily challenge: one puzzle per day, the same for everyone.
+//
+// The secret only depends on the date and the difficulty, so anybody playing
+// the same day's challenge chases the same number and can compare results
+// with the share text.
+
+use crate::date::Date;
+use crate::difficulty::{Difficulty, Settings};
+use crate::game::{Game, Status};
+use std::cmp::Ordering;
+
+// The seed for a day's challenge. This has to stay the same across versions
+// and platforms, so it is worked out by hand (SplitMix64) instead of with the
+// standard library's hasher or rand, neither of which promise that.
+pub fn seed(date: Date) -> u64 {
+    let mut z = (date.to_days() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// The day's secret number in the difficulty's range. The seed is mapped into
+// the range directly, for the same reason. The remainder favours the lower
+// numbers by less than one in 10^16, which nobody will notice.
+pub fn secret(date: Date, difficulty: Difficulty) -> u32 {
+    let settings = Settings::preset(difficulty);
+    let size = u64::from(settings.high - settings.low) + 1;
+    settings.low + (seed(date) % size) as u32
+}
+
+// A fresh game for a day's challenge.
+pub fn game(date: Date, difficulty: Difficulty) -> Game {
+    Game::new(Settings::preset(difficulty), secret(date, difficulty))
+}
+
+// A spoiler-free summary of a finished challenge to paste into chat, like
+//     Guessing game 2020-10-27 (normal) 4/7
+//     ⬆️⬇️⬆️🟩
+// Each wrong guess is an arrow pointing to where the secret was.
+pub fn share(date: Date, game: &Game) -> String {
+    let result = match game.status() {
+        Status::Won => game.guesses().to_string(),
+        _ => String::from("X"),
+    };
+    let limit = match game.settings().max_guesses {
+        Some(max) => max.to_string(),
+        None => String::from("∞"),
+    };
+
+    let trail: String = game
+        .history()
+        .iter()
+        .map(|guess| match game.secret().cmp(guess) {
+            Ordering::Greater => "⬆️",
+            Ordering::Less => "⬇️",
+            Ordering::Equal => "🟩",
+        })
+        .collect();
+
+    format!(
+        "Guessing game {} ({}) {}/{}\n{}",
+        date,
+        game.settings().difficulty,
+        result,
+        limit,
+        trail
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        text.parse().unwrap()
+    }
+
+    // These must never change, or everybody's streaks break with an update.
+    #[test]
+    fn the_seed_and_secret_of_a_day_are_fixed() {
+        // The first number SplitMix64 gives for a state of 0.
+        assert_eq!(seed(date("1970-01-01")), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(seed(date("2020-10-27")), 4_801_328_558_206_941_388);
+
+        let day = date("2020-10-27");
+        assert_eq!(secret(day, Difficulty::Easy), 39);
+        assert_eq!(secret(day, Difficulty::Normal), 89);
+        assert_eq!(secret(day, Difficulty::Hard), 389);
+        assert_eq!(game(day, Difficulty::Normal).secret(), 89);
+    }
+
+    #[test]
+    fn every_day_has_a_secret_in_range() {
+        let first = date("2000-01-01").to_days();
+        for days in first..first + 3660 {
+            for &difficulty in &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+                let settings = Settings::preset(difficulty);
+                let secret = secret(Date::from_days(days), difficulty);
+                assert!(secret >= settings.low && secret <= settings.high);
+            }
+        }
+    }
+
+    #[test]
+    fn the_share_text_hides_the_numbers() {
+        let day = date("2020-10-27");
+        let mut won = game(day, Difficulty::Normal);
+        for guess in [50, 95, 80, 89].iter() {
+            won.guess(*guess);
+        }
+        assert_eq!(
+            share(day, &won),
+            "Guessing game 2020-10-27 (normal) 4/7\n⬆️⬇️⬆️🟩"
+        );
+
+        let mut lost = game(day, Difficulty::Normal);
+        for guess in 1..=7 {
+            lost.guess(guess);
+        }
+        assert_eq!(
+            share(day, &lost),
+            "Guessing game 2020-10-27 (normal) X/7\n⬆️⬆️⬆️⬆️⬆️⬆️⬆️"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::hint::HintBands;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// A difficulty preset decides how big the secret range is and how many
+// guesses a player gets before the round is lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name.to_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            "custom" => Some(Difficulty::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// The rules for a single round: the secret is picked from low..=high and
+// the round is lost once max_guesses wrong guesses have been made.
+// A max_guesses of None means the player can keep guessing forever.
+// With count_invalid set, invalid attempts use up a guess as well.
+// When hints are set every wrong guess also gets a hot/cold hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    pub low: u32,
+    pub high: u32,
+    pub max_guesses: Option<u32>,
+    pub count_invalid: bool,
+    pub hints: Option<HintBands>,
+}
+
+impl Settings {
+    // Settings for one of the built-in presets.
+    // Custom has no preset of its own, so it starts out like Normal.
+    pub fn preset(difficulty: Difficulty) -> Settings {
+        let (low, high, max_guesses) = match difficulty {
+            Difficulty::Easy => (1, 50, 10),
+            Difficulty::Normal | Difficulty::Custom => (1, 100, 7),
+            Difficulty::Hard => (1, 1000, 10),
+        };
+
+        Settings {
+            difficulty,
+            low,
+            high,
+            max_guesses: Some(max_guesses),
+            count_invalid: false,
+            hints: None,
+        }
+    }
+
+    // Settings for a custom round. The range must contain at least one number
+    // and a guess limit, if given, must allow at least one guess.
+    pub fn custom(low: u32, high: u32, max_guesses: Option<u32>) -> Result<Settings, String> {
+        let settings = Settings {
+            difficulty: Difficulty::Custom,
+            low,
+            high,
+            max_guesses,
+            count_invalid: false,
+            hints: None,
+        };
+        settings.check()?;
+        Ok(settings)
+    }
+
+    // Whether a round can be played with these settings, for settings that
+    // come from somewhere else than custom, like a file.
+    pub fn check(&self) -> Result<(), String> {
+        if self.low > self.high {
+            return Err(format!(
+                "the lowest number ({}) is greater than the highest ({})",
+                self.low, self.high
+            ));
+        }
+        if self.high == u32::MAX {
+            return Err(format!("the highest number must be below {}", u32::MAX));
+        }
+        if self.max_guesses == Some(0) {
+            return Err(String::from("the guess limit must be at least 1"));
+        }
+        Ok(())
+    }
+
+    // The hint bands used for this difficulty when hints are turned on.
+    // Custom rounds get bands scaled to their range.
+    pub fn default_hint_bands(&self) -> HintBands {
+        match self.difficulty {
+            Difficulty::Easy => HintBands {
+                burning: 2,
+                warm: 8,
+            },
+            Difficulty::Normal => HintBands {
+                burning: 3,
+                warm: 15,
+            },
+            Difficulty::Hard => HintBands {
+                burning: 20,
+                warm: 100,
+            },
+            Difficulty::Custom => HintBands::for_range(self.low, self.high),
+        }
+    }
+}
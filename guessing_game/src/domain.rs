@@ -0,0 +1,536 @@
+// The guessing game over other kinds of secrets than numbers.
+//
+// A Domain knows how to read a guess, how to pick a secret and what to say
+// about a wrong guess. DomainGame plays a round over any of them with a guess
+// limit, no repeated guesses, and invalid input that doesn't count.
+//
+// DomainGame is a separate engine from Game on purpose. Numbers keep to Game,
+// which has rules the other secrets have no use for: a custom range, invalid
+// guesses that count, hot/cold hints, bounds for the solver, time limits and
+// transcripts. Its Feedback is also what the server, the json mode and saved
+// transcripts speak, so making Game generic would change all of those for
+// rules that only numbers have. What the two engines share is kept in one
+// place instead: Status here, and the guess limits in guess_limit below.
+
+use crate::date::Date;
+use crate::difficulty::{Difficulty, Settings};
+use crate::game::Status;
+use crate::messages::{Lang, Message};
+use crate::stats::{binary_search_guesses, optimal_guesses};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+
+pub trait Domain {
+    type Value: Clone + PartialEq + fmt::Display;
+    // What a wrong guess is told about the secret.
+    type Hint: Copy + Into<Message<'static>>;
+
+    // Tells the player what kind of secret to look for.
+    fn describe(&self) -> Message<'_>;
+    fn parse(&self, input: &str) -> Result<Self::Value, DomainInvalid>;
+    fn pick(&self, rng: &mut StdRng) -> Self::Value;
+    // None when the guess is the secret.
+    fn judge(&self, secret: &Self::Value, guess: &Self::Value) -> Option<Self::Hint>;
+    // The most guesses it takes to be sure of finding any secret, playing
+    // as well as the hints allow.
+    fn guesses_needed(&self) -> u32;
+}
+
+// A guess limit that fits the domain. The presets give as many guesses beyond
+// what the domain needs as they give beyond what their own range needs, so
+// normal and hard leave no room for mistakes and easy leaves four.
+// Custom rounds start from normal.
+pub fn guess_limit<D: Domain>(domain: &D, difficulty: Difficulty) -> u32 {
+    let preset = Settings::preset(difficulty);
+    let spare = preset
+        .max_guesses
+        .unwrap_or(0)
+        .saturating_sub(optimal_guesses(preset.low, preset.high));
+    domain.guesses_needed() + spare
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainFeedback<H> {
+    Hint(H),
+    Win,
+    // The input couldn't be taken as a guess, and why. It doesn't use up a guess.
+    Invalid(DomainInvalid),
+}
+
+impl<H: Copy + Into<Message<'static>>> fmt::Display for DomainFeedback<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.domain_feedback(self))
+    }
+}
+
+// Why an input couldn't be taken as a guess. The inputs are kept as typed so
+// the message can quote them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainInvalid {
+    NotInDictionary(String),
+    NotADate(String),
+    DateOutOfRange { first: Date, last: Date },
+    NotAColor(String),
+    // The guess as it is shown, like a color in lowercase hex.
+    Repeated(String),
+    GameOver,
+}
+
+impl fmt::Display for DomainInvalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.domain_invalid(self))
+    }
+}
+
+// A round of the guessing game over any domain.
+pub struct DomainGame<D: Domain> {
+    domain: D,
+    secret: D::Value,
+    max_guesses: Option<u32>,
+    history: Vec<D::Value>,
+    status: Status,
+}
+
+impl<D: Domain> DomainGame<D> {
+    pub fn new(domain: D, secret: D::Value, max_guesses: Option<u32>) -> DomainGame<D> {
+        DomainGame {
+            domain,
+            secret,
+            max_guesses,
+            history: Vec::new(),
+            status: Status::Playing,
+        }
+    }
+
+    // Picks the secret with a seeded generator, like Game::from_seed.
+    pub fn from_seed(domain: D, max_guesses: Option<u32>, seed: u64) -> DomainGame<D> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let secret = domain.pick(&mut rng);
+        DomainGame::new(domain, secret, max_guesses)
+    }
+
+    pub fn submit_guess(&mut self, input: &str) -> DomainFeedback<D::Hint> {
+        if self.status != Status::Playing {
+            return DomainFeedback::Invalid(DomainInvalid::GameOver);
+        }
+
+        let guess = match self.domain.parse(input.trim()) {
+            Ok(guess) => guess,
+            Err(reason) => return DomainFeedback::Invalid(reason),
+        };
+        if self.history.contains(&guess) {
+            return DomainFeedback::Invalid(DomainInvalid::Repeated(guess.to_string()));
+        }
+
+        let hint = self.domain.judge(&self.secret, &guess);
+        self.history.push(guess);
+
+        match hint {
+            None => {
+                self.status = Status::Won;
+                DomainFeedback::Win
+            }
+            Some(hint) => {
+                if self.guesses_left() == Some(0) {
+                    self.status = Status::Lost;
+                }
+                DomainFeedback::Hint(hint)
+            }
+        }
+    }
+
+    pub fn domain(&self) -> &D {
+        &self.domain
+    }
+
+    pub fn secret(&self) -> &D::Value {
+        &self.secret
+    }
+
+    pub fn guesses(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn guesses_left(&self) -> Option<u32> {
+        self.max_guesses
+            .map(|max| max.saturating_sub(self.guesses()))
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != Status::Playing
+    }
+}
+
+// Which way the secret lies from a guess, for domains with a total order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    // The secret is above the guess.
+    Higher,
+    // The secret is below the guess.
+    Lower,
+}
+
+impl Direction {
+    // None when the two are equal.
+    fn between<T: Ord>(secret: &T, guess: &T) -> Option<Direction> {
+        match secret.cmp(guess) {
+            Ordering::Greater => Some(Direction::Higher),
+            Ordering::Less => Some(Direction::Lower),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+// A word from a dictionary, with hints in alphabetical order.
+#[derive(Debug, Clone)]
+pub struct Words {
+    words: Vec<String>,
+}
+
+// A small dictionary for when no word list is given.
+pub const DEFAULT_WORDS: &[&str] = &[
+    "apple", "banana", "cherry", "dragon", "eagle", "forest", "guitar", "harbor", "island",
+    "jungle", "kettle", "lemon", "mirror", "needle", "orange", "pepper", "quartz", "rabbit",
+    "saddle", "tomato", "umbrella", "violin", "walrus", "yogurt", "zebra",
+];
+
+impl Words {
+    // Builds a dictionary from any list of words. Words are lowercased and
+    // duplicates dropped; at least one word has to be left.
+    pub fn new<I, S>(words: I) -> Result<Words, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words: Vec<String> = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        words.sort();
+        words.dedup();
+
+        if words.is_empty() {
+            return Err(String::from("the dictionary has no words in it"));
+        }
+        Ok(Words { words })
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+impl Default for Words {
+    fn default() -> Words {
+        Words::new(DEFAULT_WORDS).expect("the default dictionary isn't empty")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordHint(pub Direction);
+
+impl From<WordHint> for Message<'static> {
+    fn from(hint: WordHint) -> Message<'static> {
+        Message::WordHint(hint.0)
+    }
+}
+
+impl fmt::Display for WordHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.text((*self).into()))
+    }
+}
+
+impl Domain for Words {
+    type Value = String;
+    type Hint = WordHint;
+
+    fn describe(&self) -> Message<'_> {
+        Message::DescribeWords {
+            count: self.words.len(),
+            first: &self.words[0],
+            last: &self.words[self.words.len() - 1],
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<String, DomainInvalid> {
+        let word = input.to_lowercase();
+        if self.words.binary_search(&word).is_ok() {
+            Ok(word)
+        } else {
+            Err(DomainInvalid::NotInDictionary(input.to_string()))
+        }
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> String {
+        self.words[rng.gen_range(0, self.words.len())].clone()
+    }
+
+    fn judge(&self, secret: &String, guess: &String) -> Option<WordHint> {
+        Direction::between(secret, guess).map(WordHint)
+    }
+
+    fn guesses_needed(&self) -> u32 {
+        binary_search_guesses(self.words.len() as u64)
+    }
+}
+
+// A day in first..=last.
+#[derive(Debug, Clone, Copy)]
+pub struct Dates {
+    pub first: Date,
+    pub last: Date,
+}
+
+impl Default for Dates {
+    fn default() -> Dates {
+        Dates {
+            first: Date::new(2000, 1, 1).expect("a real date"),
+            last: Date::new(2029, 12, 31).expect("a real date"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateHint(pub Direction);
+
+impl From<DateHint> for Message<'static> {
+    fn from(hint: DateHint) -> Message<'static> {
+        Message::DateHint(hint.0)
+    }
+}
+
+impl fmt::Display for DateHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.text((*self).into()))
+    }
+}
+
+impl Domain for Dates {
+    type Value = Date;
+    type Hint = DateHint;
+
+    fn describe(&self) -> Message<'_> {
+        Message::DescribeDates {
+            first: self.first,
+            last: self.last,
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Date, DomainInvalid> {
+        let date: Date = input
+            .parse()
+            .map_err(|_| DomainInvalid::NotADate(input.to_string()))?;
+        if date < self.first || date > self.last {
+            return Err(DomainInvalid::DateOutOfRange {
+                first: self.first,
+                last: self.last,
+            });
+        }
+        Ok(date)
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> Date {
+        Date::from_days(rng.gen_range(self.first.to_days(), self.last.to_days() + 1))
+    }
+
+    fn judge(&self, secret: &Date, guess: &Date) -> Option<DateHint> {
+        Direction::between(secret, guess).map(DateHint)
+    }
+
+    fn guesses_needed(&self) -> u32 {
+        let days = self.last.to_days() - self.first.to_days() + 1;
+        binary_search_guesses(days.max(1) as u64)
+    }
+}
+
+// A hex color like #3a7bd5. Colors have no single order, so every wrong guess
+// is told for each channel whether it was too low, too high or spot on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Colors;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorHint {
+    // None for a channel that was guessed right.
+    pub channels: [Option<Direction>; 3],
+}
+
+impl From<ColorHint> for Message<'static> {
+    fn from(hint: ColorHint) -> Message<'static> {
+        Message::ColorHint(hint.channels)
+    }
+}
+
+impl fmt::Display for ColorHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.text((*self).into()))
+    }
+}
+
+impl Domain for Colors {
+    type Value = Color;
+    type Hint = ColorHint;
+
+    fn describe(&self) -> Message<'_> {
+        Message::DescribeColors
+    }
+
+    fn parse(&self, input: &str) -> Result<Color, DomainInvalid> {
+        let hex = input.strip_prefix('#').unwrap_or(input);
+        let invalid = || DomainInvalid::NotAColor(input.to_string());
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| invalid());
+        Ok(Color {
+            red: channel(0)?,
+            green: channel(2)?,
+            blue: channel(4)?,
+        })
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> Color {
+        Color {
+            red: rng.gen(),
+            green: rng.gen(),
+            blue: rng.gen(),
+        }
+    }
+
+    fn judge(&self, secret: &Color, guess: &Color) -> Option<ColorHint> {
+        let channels = [
+            Direction::between(&secret.red, &guess.red),
+            Direction::between(&secret.green, &guess.green),
+            Direction::between(&secret.blue, &guess.blue),
+        ];
+        if channels.iter().all(Option::is_none) {
+            None
+        } else {
+            Some(ColorHint { channels })
+        }
+    }
+
+    // Every guess narrows down all three channels at once, so it takes as
+    // many guesses as one channel of 256 values.
+    fn guesses_needed(&self) -> u32 {
+        binary_search_guesses(256)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_limit_is_what_the_domain_needs_plus_the_spare_guesses() {
+        let dates = Dates::default();
+        // 10,958 days.
+        assert_eq!(dates.guesses_needed(), 14);
+        assert_eq!(guess_limit(&dates, Difficulty::Normal), 14);
+        assert_eq!(guess_limit(&dates, Difficulty::Hard), 14);
+        assert_eq!(guess_limit(&dates, Difficulty::Easy), 18);
+        assert_eq!(guess_limit(&dates, Difficulty::Custom), 14);
+
+        assert_eq!(guess_limit(&Colors, Difficulty::Normal), 9);
+        // 25 words.
+        assert_eq!(guess_limit(&Words::default(), Difficulty::Normal), 5);
+
+        let one_day = Dates {
+            first: Date::new(2024, 2, 29).unwrap(),
+            last: Date::new(2024, 2, 29).unwrap(),
+        };
+        assert_eq!(guess_limit(&one_day, Difficulty::Normal), 1);
+    }
+
+    #[test]
+    fn a_binary_search_finds_any_date_within_the_limit() {
+        let dates = Dates::default();
+        let limit = guess_limit(&dates, Difficulty::Normal);
+        for seed in 0..200 {
+            let mut game = DomainGame::from_seed(dates, Some(limit), seed);
+            let (mut low, mut high) = (dates.first.to_days(), dates.last.to_days());
+            while !game.is_over() {
+                let guess = Date::from_days(low + (high - low) / 2);
+                match game.submit_guess(&guess.to_string()) {
+                    DomainFeedback::Hint(DateHint(Direction::Higher)) => low = guess.to_days() + 1,
+                    DomainFeedback::Hint(DateHint(Direction::Lower)) => high = guess.to_days() - 1,
+                    DomainFeedback::Win => {}
+                    DomainFeedback::Invalid(reason) => panic!("{}", reason),
+                }
+            }
+            assert_eq!(game.status(), Status::Won, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn a_search_on_every_channel_finds_any_color_within_the_limit() {
+        let limit = guess_limit(&Colors, Difficulty::Normal);
+        for seed in 0..200 {
+            let mut game = DomainGame::from_seed(Colors, Some(limit), seed);
+            let mut ranges = [(0u32, 255u32); 3];
+            while !game.is_over() {
+                let middle: Vec<u8> = ranges
+                    .iter()
+                    .map(|&(low, high)| (low + (high - low) / 2) as u8)
+                    .collect();
+                let guess = Color {
+                    red: middle[0],
+                    green: middle[1],
+                    blue: middle[2],
+                };
+                if let DomainFeedback::Hint(hint) = game.submit_guess(&guess.to_string()) {
+                    for (range, (channel, &value)) in ranges
+                        .iter_mut()
+                        .zip(hint.channels.iter().zip(middle.iter()))
+                    {
+                        match channel {
+                            Some(Direction::Higher) => range.0 = u32::from(value) + 1,
+                            Some(Direction::Lower) => range.1 = u32::from(value) - 1,
+                            None => *range = (u32::from(value), u32::from(value)),
+                        }
+                    }
+                }
+            }
+            assert_eq!(game.status(), Status::Won, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn words_are_told_apart_alphabetically() {
+        let words = Words::new(vec!["Pear", "apple", "pear", " fig "]).unwrap();
+        assert_eq!(words.words(), ["apple", "fig", "pear"]);
+        assert!(words.parse("kiwi").is_err());
+
+        let mut game = DomainGame::new(words, String::from("fig"), Some(2));
+        match game.submit_guess("APPLE") {
+            DomainFeedback::Hint(WordHint(direction)) => assert_eq!(direction, Direction::Higher),
+            _ => panic!("apple is not the secret"),
+        }
+        assert_eq!(
+            game.submit_guess("apple"),
+            DomainFeedback::Invalid(DomainInvalid::Repeated(String::from("apple")))
+        );
+        assert!(matches!(game.submit_guess("fig"), DomainFeedback::Win));
+    }
+}
@@ -0,0 +1,424 @@
+use crate::difficulty::Settings;
+use crate::hint::Hint;
+use crate::messages::Lang;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+
+// What the game answers to a single guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feedback {
+    TooSmall,
+    TooBig,
+    Win,
+    // The input wasn't a guess the game could accept. Unless the settings say
+    // otherwise it doesn't use up a guess.
+    Invalid(InvalidGuess),
+}
+
+// Why a guess was turned down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidGuess {
+    NotANumber,
+    Negative,
+    OutOfRange { low: u32, high: u32 },
+    Repeated(u32),
+    // The round was already won or lost.
+    GameOver,
+}
+
+impl InvalidGuess {
+    // A short name for the reason, for programs rather than people. The
+    // server and the json mode both send it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            InvalidGuess::NotANumber => "not_a_number",
+            InvalidGuess::Negative => "negative",
+            InvalidGuess::OutOfRange { .. } => "out_of_range",
+            InvalidGuess::Repeated(_) => "repeated",
+            InvalidGuess::GameOver => "game_over",
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.feedback(self))
+    }
+}
+
+impl fmt::Display for InvalidGuess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.invalid(self))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Playing,
+    Won,
+    // The guess limit was used up without finding the secret.
+    Lost,
+}
+
+// A single round of the guessing game as a state machine.
+// Each call to submit_guess moves the game along until it is Won or Lost.
+#[derive(Debug, Clone)]
+pub struct Game {
+    settings: Settings,
+    secret: u32,
+    guesses: u32,
+    history: Vec<u32>,
+    status: Status,
+}
+
+impl Game {
+    pub fn new(settings: Settings, secret: u32) -> Game {
+        Game {
+            settings,
+            secret,
+            guesses: 0,
+            history: Vec::new(),
+            status: Status::Playing,
+        }
+    }
+
+    // Picks the secret from the settings' range using a seeded generator,
+    // so the same settings and seed always produce the same round.
+    pub fn from_seed(settings: Settings, seed: u64) -> Game {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let secret = rng.gen_range(settings.low, settings.high + 1);
+        Game::new(settings, secret)
+    }
+
+    // Takes a line of player input and answers it.
+    pub fn submit_guess(&mut self, input: &str) -> Feedback {
+        match self.parse(input.trim()) {
+            Ok(guess) => self.guess(guess),
+            Err(reason) => self.reject(reason),
+        }
+    }
+
+    // Answers a guess that is already a number, for front ends that never
+    // deal with typed input such as the solver.
+    pub fn guess(&mut self, guess: u32) -> Feedback {
+        if let Err(reason) = self.check(guess) {
+            return self.reject(reason);
+        }
+
+        self.history.push(guess);
+        self.count_guess();
+
+        let feedback = match guess.cmp(&self.secret) {
+            Ordering::Less => Feedback::TooSmall,
+            Ordering::Greater => Feedback::TooBig,
+            Ordering::Equal => Feedback::Win,
+        };
+
+        if feedback == Feedback::Win {
+            self.status = Status::Won;
+        }
+
+        feedback
+    }
+
+    // Turns the input into a number, or says why it can't be one.
+    fn parse(&self, input: &str) -> Result<u32, InvalidGuess> {
+        let guess: i64 = match input.parse() {
+            Ok(num) => num,
+            // Too many digits to fit, but still clearly a number.
+            Err(_) if is_integer(input) => {
+                return Err(if input.starts_with('-') {
+                    InvalidGuess::Negative
+                } else {
+                    self.out_of_range()
+                });
+            }
+            Err(_) => return Err(InvalidGuess::NotANumber),
+        };
+
+        if guess < 0 {
+            return Err(InvalidGuess::Negative);
+        }
+        if guess > i64::from(u32::MAX) {
+            return Err(self.out_of_range());
+        }
+
+        Ok(guess as u32)
+    }
+
+    // Checks a number against the rules of the round.
+    fn check(&self, guess: u32) -> Result<(), InvalidGuess> {
+        if self.status != Status::Playing {
+            return Err(InvalidGuess::GameOver);
+        }
+        if guess < self.settings.low || guess > self.settings.high {
+            return Err(self.out_of_range());
+        }
+        if self.history.contains(&guess) {
+            return Err(InvalidGuess::Repeated(guess));
+        }
+
+        Ok(())
+    }
+
+    // Turns a guess down, using up a guess if the settings say so.
+    fn reject(&mut self, reason: InvalidGuess) -> Feedback {
+        if self.status != Status::Playing {
+            return Feedback::Invalid(InvalidGuess::GameOver);
+        }
+        if self.settings.count_invalid {
+            self.count_guess();
+        }
+        Feedback::Invalid(reason)
+    }
+
+    fn out_of_range(&self) -> InvalidGuess {
+        InvalidGuess::OutOfRange {
+            low: self.settings.low,
+            high: self.settings.high,
+        }
+    }
+
+    // Uses up one guess, losing the round when that was the last one.
+    fn count_guess(&mut self) {
+        self.guesses += 1;
+        if self.guesses_left() == Some(0) {
+            self.status = Status::Lost;
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    // How many guesses have been counted so far.
+    pub fn guesses(&self) -> u32 {
+        self.guesses
+    }
+
+    // The hot/cold hint for the latest guess, when the settings ask for hints
+    // and the round is still going.
+    pub fn hint(&self) -> Option<Hint> {
+        let bands = self.settings.hints?;
+        if self.status != Status::Playing {
+            return None;
+        }
+
+        let (&guess, earlier) = self.history.split_last()?;
+        Some(Hint::new(
+            &bands,
+            self.secret,
+            guess,
+            earlier.last().copied(),
+        ))
+    }
+
+    // The range the secret can still be in, going by the answers so far.
+    pub fn bounds(&self) -> (u32, u32) {
+        let mut low = self.settings.low;
+        let mut high = self.settings.high;
+        for &guess in &self.history {
+            match guess.cmp(&self.secret) {
+                Ordering::Less => low = low.max(guess + 1),
+                Ordering::Greater => high = high.min(guess - 1),
+                Ordering::Equal => return (guess, guess),
+            }
+        }
+        (low, high)
+    }
+
+    // Every accepted guess, oldest first.
+    pub fn history(&self) -> &[u32] {
+        &self.history
+    }
+
+    // None when the round has no guess limit.
+    pub fn guesses_left(&self) -> Option<u32> {
+        self.settings
+            .max_guesses
+            .map(|max| max.saturating_sub(self.guesses))
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    // Loses a round that is still going because the player ran out of time.
+    pub fn time_out(&mut self) {
+        if self.status == Status::Playing {
+            self.status = Status::Lost;
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != Status::Playing
+    }
+}
+
+fn is_integer(input: &str) -> bool {
+    let digits = input.strip_prefix('-').unwrap_or(input);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+
+    // A normal round, 1 to 100 with 7 guesses, where the secret is 42.
+    fn game() -> Game {
+        Game::new(Settings::preset(Difficulty::Normal), 42)
+    }
+
+    fn game_won() -> Game {
+        let mut game = game();
+        game.guess(42);
+        game
+    }
+
+    #[test]
+    fn a_guess_below_the_secret_is_too_small() {
+        let mut game = game();
+        assert_eq!(game.submit_guess("41"), Feedback::TooSmall);
+        assert_eq!(game.status(), Status::Playing);
+        assert_eq!(game.guesses(), 1);
+    }
+
+    #[test]
+    fn a_guess_above_the_secret_is_too_big() {
+        let mut game = game();
+        assert_eq!(game.submit_guess("43"), Feedback::TooBig);
+        assert_eq!(game.status(), Status::Playing);
+        assert_eq!(game.guesses(), 1);
+    }
+
+    #[test]
+    fn the_secret_wins() {
+        let mut game = game();
+        assert_eq!(game.submit_guess(" 42\n"), Feedback::Win);
+        assert_eq!(game.status(), Status::Won);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn the_round_is_lost_when_the_guesses_run_out() {
+        let mut game = game();
+        for guess in 1..7 {
+            assert_eq!(game.guess(guess), Feedback::TooSmall);
+            assert_eq!(game.guesses_left(), Some(7 - guess));
+        }
+        assert_eq!(game.guess(7), Feedback::TooSmall);
+        assert_eq!(game.status(), Status::Lost);
+        assert_eq!(game.guesses_left(), Some(0));
+    }
+
+    #[test]
+    fn a_winning_last_guess_still_wins() {
+        let mut game = game();
+        for guess in 1..7 {
+            game.guess(guess);
+        }
+        assert_eq!(game.guess(42), Feedback::Win);
+        assert_eq!(game.status(), Status::Won);
+    }
+
+    #[test]
+    fn invalid_input_says_why() {
+        let mut game = game();
+        assert_eq!(
+            game.submit_guess("forty"),
+            Feedback::Invalid(InvalidGuess::NotANumber)
+        );
+        assert_eq!(
+            game.submit_guess(""),
+            Feedback::Invalid(InvalidGuess::NotANumber)
+        );
+        assert_eq!(
+            game.submit_guess("-5"),
+            Feedback::Invalid(InvalidGuess::Negative)
+        );
+        assert_eq!(
+            game.submit_guess("-99999999999999999999"),
+            Feedback::Invalid(InvalidGuess::Negative)
+        );
+
+        let out_of_range = Feedback::Invalid(InvalidGuess::OutOfRange { low: 1, high: 100 });
+        assert_eq!(game.submit_guess("0"), out_of_range);
+        assert_eq!(game.submit_guess("101"), out_of_range);
+        assert_eq!(game.submit_guess("4294967296"), out_of_range);
+        assert_eq!(game.submit_guess("99999999999999999999"), out_of_range);
+
+        game.submit_guess("10");
+        assert_eq!(
+            game.submit_guess("10"),
+            Feedback::Invalid(InvalidGuess::Repeated(10))
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_free_unless_the_settings_count_it() {
+        let mut game = game();
+        game.submit_guess("nope");
+        assert_eq!(game.guesses(), 0);
+
+        let mut settings = Settings::preset(Difficulty::Normal);
+        settings.count_invalid = true;
+        settings.max_guesses = Some(1);
+        let mut game = Game::new(settings, 42);
+        game.submit_guess("nope");
+        assert_eq!(game.guesses(), 1);
+        assert_eq!(game.status(), Status::Lost);
+    }
+
+    #[test]
+    fn nothing_is_taken_once_the_round_is_over() {
+        let mut game = game();
+        game.guess(42);
+        assert_eq!(game.guess(10), Feedback::Invalid(InvalidGuess::GameOver));
+        assert_eq!(
+            game.submit_guess("nope"),
+            Feedback::Invalid(InvalidGuess::GameOver)
+        );
+        assert_eq!(game.guesses(), 1);
+        assert_eq!(game.history(), [42]);
+    }
+
+    #[test]
+    fn bounds_narrow_with_each_answer() {
+        let mut game = game();
+        assert_eq!(game.bounds(), (1, 100));
+        game.guess(50);
+        assert_eq!(game.bounds(), (1, 49));
+        game.guess(20);
+        assert_eq!(game.bounds(), (21, 49));
+        game.guess(42);
+        assert_eq!(game.bounds(), (42, 42));
+    }
+
+    #[test]
+    fn a_timed_out_round_is_lost() {
+        let mut game = game();
+        game.time_out();
+        assert_eq!(game.status(), Status::Lost);
+
+        let mut game = game_won();
+        game.time_out();
+        assert_eq!(game.status(), Status::Won);
+    }
+
+    #[test]
+    fn the_same_seed_picks_the_same_secret() {
+        let settings = Settings::preset(Difficulty::Hard);
+        for seed in 0..100 {
+            let secret = Game::from_seed(settings, seed).secret();
+            assert_eq!(Game::from_seed(settings, seed).secret(), secret);
+            assert!(secret >= settings.low && secret <= settings.high);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::messages::Lang;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// How far away a guess is from the secret, in broad strokes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Band {
+    Burning,
+    Warm,
+    Cold,
+}
+
+// How the latest guess compares to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trend {
+    Closer,
+    Farther,
+    Same,
+}
+
+// The hot/cold hint given after a wrong guess.
+// There is no trend for the first guess of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Hint {
+    pub band: Band,
+    pub trend: Option<Trend>,
+}
+
+// The largest distances from the secret that still count as burning and warm.
+// Anything further away is cold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintBands {
+    pub burning: u32,
+    pub warm: u32,
+}
+
+impl HintBands {
+    // Bands that are about a twentieth and a fifth of a range this size.
+    pub fn for_range(low: u32, high: u32) -> HintBands {
+        let size = high - low + 1;
+        let burning = (size / 20).max(1);
+        HintBands {
+            burning,
+            warm: (size / 5).max(burning + 1),
+        }
+    }
+
+    pub fn band(&self, distance: u32) -> Band {
+        if distance <= self.burning {
+            Band::Burning
+        } else if distance <= self.warm {
+            Band::Warm
+        } else {
+            Band::Cold
+        }
+    }
+}
+
+impl Hint {
+    // The hint for a guess, given the one before it, if any.
+    pub fn new(bands: &HintBands, secret: u32, guess: u32, previous: Option<u32>) -> Hint {
+        let distance = secret.abs_diff(guess);
+        let trend = previous.map(|previous| {
+            let before = secret.abs_diff(previous);
+            if distance < before {
+                Trend::Closer
+            } else if distance > before {
+                Trend::Farther
+            } else {
+                Trend::Same
+            }
+        });
+
+        Hint {
+            band: bands.band(distance),
+            trend,
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.hint(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::{Difficulty, Settings};
+    use crate::game::Game;
+
+    const BANDS: HintBands = HintBands {
+        burning: 3,
+        warm: 15,
+    };
+
+    #[test]
+    fn bands_include_their_edges() {
+        assert_eq!(BANDS.band(0), Band::Burning);
+        assert_eq!(BANDS.band(3), Band::Burning);
+        assert_eq!(BANDS.band(4), Band::Warm);
+        assert_eq!(BANDS.band(15), Band::Warm);
+        assert_eq!(BANDS.band(16), Band::Cold);
+        assert_eq!(BANDS.band(u32::MAX), Band::Cold);
+    }
+
+    #[test]
+    fn the_first_guess_has_no_trend() {
+        let hint = Hint::new(&BANDS, 50, 60, None);
+        assert_eq!(
+            hint,
+            Hint {
+                band: Band::Warm,
+                trend: None
+            }
+        );
+    }
+
+    #[test]
+    fn the_trend_compares_distances_on_either_side() {
+        assert_eq!(
+            Hint::new(&BANDS, 50, 52, Some(40)).trend,
+            Some(Trend::Closer)
+        );
+        assert_eq!(
+            Hint::new(&BANDS, 50, 10, Some(60)).trend,
+            Some(Trend::Farther)
+        );
+        assert_eq!(Hint::new(&BANDS, 50, 45, Some(55)).trend, Some(Trend::Same));
+    }
+
+    #[test]
+    fn bands_for_a_range_scale_with_it() {
+        assert_eq!(
+            HintBands::for_range(1, 1000),
+            HintBands {
+                burning: 50,
+                warm: 200
+            }
+        );
+        // Even a tiny range has a burning band and a wider warm one.
+        assert_eq!(
+            HintBands::for_range(1, 3),
+            HintBands {
+                burning: 1,
+                warm: 2
+            }
+        );
+        assert_eq!(
+            HintBands::for_range(0, u32::MAX - 1),
+            HintBands {
+                burning: u32::MAX / 20,
+                warm: u32::MAX / 5
+            }
+        );
+    }
+
+    #[test]
+    fn a_seeded_round_gives_the_hints_for_its_secret() {
+        let mut settings = Settings::preset(Difficulty::Normal);
+        settings.hints = Some(settings.default_hint_bands());
+        let mut game = Game::from_seed(settings, 42);
+        let secret = game.secret();
+
+        // A guess from the far end of the range, one right next to the
+        // secret, then one a bit further away again.
+        let far = if secret > 50 { 1 } else { 100 };
+        let near = if secret > 50 { secret - 2 } else { secret + 2 };
+        game.guess(far);
+        assert_eq!(
+            game.hint(),
+            Some(Hint {
+                band: BANDS.band(secret.abs_diff(far)),
+                trend: None
+            })
+        );
+        assert_eq!(game.hint().unwrap().band, Band::Cold);
+
+        game.guess(near);
+        assert_eq!(
+            game.hint(),
+            Some(Hint {
+                band: Band::Burning,
+                trend: Some(Trend::Closer)
+            })
+        );
+
+        let farther = if secret > 50 {
+            secret - 10
+        } else {
+            secret + 10
+        };
+        game.guess(farther);
+        assert_eq!(
+            game.hint(),
+            Some(Hint {
+                band: Band::Warm,
+                trend: Some(Trend::Farther)
+            })
+        );
+
+        // No hint once the round is won.
+        game.guess(secret);
+        assert_eq!(game.hint(), None);
+    }
+
+    #[test]
+    fn rounds_without_hints_give_none() {
+        let mut game = Game::from_seed(Settings::preset(Difficulty::Normal), 42);
+        game.guess(if game.secret() == 1 { 2 } else { 1 });
+        assert_eq!(game.hint(), None);
+    }
+}
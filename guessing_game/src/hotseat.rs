@@ -0,0 +1,340 @@
+use crate::difficulty::Settings;
+use crate::game::{Feedback, Game, Status};
+
+// One round of local multiplayer. Every player has a Game of their own so
+// guesses are counted separately, and they take turns at the keyboard.
+// With a shared secret the first player to find it wins the round; otherwise
+// everyone chases their own secret and the fewest guesses wins.
+#[derive(Debug, Clone)]
+pub struct Round {
+    games: Vec<Game>,
+    shared: bool,
+    turn: usize,
+}
+
+impl Round {
+    // The secrets come from the seed: a shared secret uses it as is, separate
+    // secrets use seed + the player's position.
+    pub fn new(players: usize, settings: Settings, seed: u64, shared: bool) -> Round {
+        let games = (0..players)
+            .map(|player| {
+                let seed = if shared {
+                    seed
+                } else {
+                    seed.wrapping_add(player as u64)
+                };
+                Game::from_seed(settings, seed)
+            })
+            .collect();
+
+        Round {
+            games,
+            shared,
+            turn: 0,
+        }
+    }
+
+    // Whose turn it is.
+    pub fn current_player(&self) -> usize {
+        self.turn
+    }
+
+    // Answers the current player's guess and passes the keyboard on, unless
+    // the guess was turned down without costing anything.
+    pub fn submit_guess(&mut self, input: &str) -> Feedback {
+        let game = &mut self.games[self.turn];
+        let guesses = game.guesses();
+        let feedback = game.submit_guess(input);
+
+        if game.guesses() > guesses {
+            self.next_turn();
+        }
+        feedback
+    }
+
+    // Moves on to the next player who is still playing.
+    fn next_turn(&mut self) {
+        if self.is_over() {
+            return;
+        }
+        loop {
+            self.turn = (self.turn + 1) % self.games.len();
+            if !self.games[self.turn].is_over() {
+                break;
+            }
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        if self.shared && self.games.iter().any(|game| game.status() == Status::Won) {
+            return true;
+        }
+        self.games.iter().all(Game::is_over)
+    }
+
+    // The winner of a finished round, or None when nobody won or the best
+    // players tied.
+    pub fn winner(&self) -> Option<usize> {
+        if !self.is_over() {
+            return None;
+        }
+
+        let winners: Vec<(usize, u32)> = self
+            .games
+            .iter()
+            .enumerate()
+            .filter(|(_, game)| game.status() == Status::Won)
+            .map(|(player, game)| (player, game.guesses()))
+            .collect();
+
+        let best = winners.iter().map(|&(_, guesses)| guesses).min()?;
+        let mut best_players = winners.iter().filter(|&&(_, guesses)| guesses == best);
+        match (best_players.next(), best_players.next()) {
+            (Some(&(player, _)), None) => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn game(&self, player: usize) -> &Game {
+        &self.games[player]
+    }
+
+    pub fn players(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+}
+
+// A best-of-N match between named players.
+#[derive(Debug, Clone)]
+pub struct Match {
+    players: Vec<String>,
+    best_of: u32,
+    wins: Vec<u32>,
+    played: u32,
+}
+
+impl Match {
+    pub fn new(players: Vec<String>, best_of: u32) -> Match {
+        let wins = vec![0; players.len()];
+        Match {
+            players,
+            best_of,
+            wins,
+            played: 0,
+        }
+    }
+
+    // Counts a finished round. A round without a winner still counts as played.
+    pub fn record(&mut self, winner: Option<usize>) {
+        self.played += 1;
+        if let Some(player) = winner {
+            self.wins[player] += 1;
+        }
+    }
+
+    // Over once every round is played or somebody has won more than half of them.
+    pub fn is_over(&self) -> bool {
+        self.played >= self.best_of || self.wins.iter().any(|&wins| wins * 2 > self.best_of)
+    }
+
+    // The player with the most round wins, or None on a tie.
+    pub fn leader(&self) -> Option<usize> {
+        let best = *self.wins.iter().max()?;
+        let mut leaders = self
+            .wins
+            .iter()
+            .enumerate()
+            .filter(|&(_, &wins)| wins == best);
+        match (leaders.next(), leaders.next()) {
+            (Some((player, _)), None) if best > 0 => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn players(&self) -> &[String] {
+        &self.players
+    }
+
+    pub fn wins(&self) -> &[u32] {
+        &self.wins
+    }
+
+    pub fn played(&self) -> u32 {
+        self.played
+    }
+
+    pub fn best_of(&self) -> u32 {
+        self.best_of
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+
+    const SEED: u64 = 11;
+
+    fn round(players: usize, shared: bool) -> Round {
+        Round::new(players, Settings::preset(Difficulty::Normal), SEED, shared)
+    }
+
+    // The current player finds their secret.
+    fn hit(round: &mut Round) -> Feedback {
+        let secret = round.game(round.current_player()).secret();
+        round.submit_guess(&secret.to_string())
+    }
+
+    // The current player guesses a number that isn't their secret and
+    // that they haven't tried yet.
+    fn miss(round: &mut Round) -> Feedback {
+        let game = round.game(round.current_player());
+        let guess = (1..=100)
+            .find(|n| *n != game.secret() && !game.history().contains(n))
+            .unwrap();
+        round.submit_guess(&guess.to_string())
+    }
+
+    #[test]
+    fn separate_secrets_come_from_the_seed_and_the_position() {
+        let round = round(3, false);
+        for player in 0..3 {
+            let expected = Game::from_seed(*round.game(player).settings(), SEED + player as u64);
+            assert_eq!(round.game(player).secret(), expected.secret());
+        }
+
+        let round = self::round(3, true);
+        assert!(round.is_shared());
+        assert_eq!(round.game(1).secret(), round.game(0).secret());
+        assert_eq!(round.game(2).secret(), round.game(0).secret());
+    }
+
+    #[test]
+    fn the_keyboard_goes_round_after_every_counted_guess() {
+        let mut round = round(3, false);
+        assert_eq!(round.current_player(), 0);
+        miss(&mut round);
+        assert_eq!(round.current_player(), 1);
+        miss(&mut round);
+        miss(&mut round);
+        assert_eq!(round.current_player(), 0);
+        assert_eq!(round.game(0).guesses(), 1);
+        assert_eq!(round.game(1).guesses(), 1);
+    }
+
+    #[test]
+    fn a_guess_that_costs_nothing_keeps_the_turn() {
+        let mut round = round(2, false);
+        assert!(matches!(round.submit_guess("ten"), Feedback::Invalid(_)));
+        assert_eq!(round.current_player(), 0);
+
+        let mut settings = Settings::preset(Difficulty::Normal);
+        settings.count_invalid = true;
+        let mut round = Round::new(2, settings, SEED, false);
+        round.submit_guess("ten");
+        assert_eq!(round.current_player(), 1);
+    }
+
+    #[test]
+    fn players_who_are_done_are_skipped() {
+        let mut round = round(3, false);
+        assert_eq!(hit(&mut round), Feedback::Win);
+        assert!(!round.is_over());
+        miss(&mut round);
+        assert_eq!(round.current_player(), 2);
+        miss(&mut round);
+        // Player 0 has found their secret, so it is player 1's turn again.
+        assert_eq!(round.current_player(), 1);
+    }
+
+    #[test]
+    fn a_shared_secret_ends_the_round_when_somebody_finds_it() {
+        let mut round = round(3, true);
+        miss(&mut round);
+        assert_eq!(hit(&mut round), Feedback::Win);
+        assert!(round.is_over());
+        assert_eq!(round.winner(), Some(1));
+        // Player 2 never got to guess.
+        assert_eq!(round.game(2).guesses(), 0);
+    }
+
+    #[test]
+    fn separate_secrets_go_to_the_fewest_guesses() {
+        let mut round = round(2, false);
+        miss(&mut round);
+        hit(&mut round);
+        assert!(!round.is_over());
+        assert_eq!(round.winner(), None);
+        hit(&mut round);
+        assert!(round.is_over());
+        assert_eq!(round.winner(), Some(1));
+    }
+
+    #[test]
+    fn a_tie_or_nobody_finding_their_secret_has_no_winner() {
+        let mut round = round(2, false);
+        hit(&mut round);
+        hit(&mut round);
+        assert!(round.is_over());
+        assert_eq!(round.winner(), None);
+
+        let mut round = self::round(2, false);
+        while !round.is_over() {
+            miss(&mut round);
+        }
+        assert_eq!(round.game(0).status(), Status::Lost);
+        assert_eq!(round.game(1).status(), Status::Lost);
+        assert_eq!(round.winner(), None);
+    }
+
+    fn best_of_three() -> Match {
+        Match::new(vec![String::from("ada"), String::from("bob")], 3)
+    }
+
+    #[test]
+    fn a_match_is_over_once_somebody_has_won_more_than_half() {
+        let mut game = best_of_three();
+        game.record(Some(0));
+        assert!(!game.is_over());
+        assert_eq!(game.leader(), Some(0));
+        game.record(Some(0));
+        assert!(game.is_over());
+        assert_eq!(game.played(), 2);
+        assert_eq!(game.wins(), [2, 0]);
+        assert_eq!(game.leader(), Some(0));
+    }
+
+    #[test]
+    fn a_match_is_over_once_every_round_is_played() {
+        let mut game = best_of_three();
+        game.record(Some(0));
+        game.record(None);
+        game.record(Some(1));
+        assert!(game.is_over());
+        // One win each is a draw.
+        assert_eq!(game.leader(), None);
+
+        let mut game = best_of_three();
+        for _ in 0..3 {
+            assert_eq!(game.leader(), None);
+            game.record(None);
+        }
+        assert!(game.is_over());
+        assert_eq!(game.leader(), None);
+    }
+
+    #[test]
+    fn half_the_rounds_of_an_even_match_is_not_enough() {
+        let mut game = Match::new(vec![String::from("ada"), String::from("bob")], 4);
+        game.record(Some(1));
+        game.record(Some(1));
+        assert!(!game.is_over());
+        game.record(Some(1));
+        assert!(game.is_over());
+        assert_eq!(game.leader(), Some(1));
+    }
+}
@@ -0,0 +1,89 @@
+// The files the game keeps between runs, scores, ratings and transcripts, are
+// all pretty-printed JSON. Reading one that isn't valid JSON, or not the shape
+// expected, is an InvalidData error.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub fn load<T, P>(path: P) -> io::Result<T>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Like load, but a file that doesn't exist yet is the empty default, for
+// files that are only written once there is something to keep.
+pub fn load_or_default<T, P>(path: P) -> io::Result<T>
+where
+    T: DeserializeOwned + Default,
+    P: AsRef<Path>,
+{
+    match load(path) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        result => result,
+    }
+}
+
+pub fn save<T, P>(value: &T, path: P) -> io::Result<()>
+where
+    T: Serialize,
+    P: AsRef<Path>,
+{
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, contents + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::env;
+    use std::path::PathBuf;
+
+    // A file of the test's own in the temporary directory.
+    fn temp_file(test: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "guessing_game_json_file_{}_{}.json",
+            test,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn what_is_saved_loads_back() {
+        let path = temp_file("round_trip");
+        let value: BTreeMap<String, u32> = vec![(String::from("ada"), 3)].into_iter().collect();
+        save(&value, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let loaded: io::Result<BTreeMap<String, u32>> = load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(contents, "{\n  \"ada\": 3\n}\n");
+        assert_eq!(loaded.unwrap(), value);
+    }
+
+    #[test]
+    fn a_missing_file_is_an_error_or_the_default() {
+        let path = temp_file("missing");
+        let loaded: io::Result<Vec<u32>> = load(&path);
+        assert_eq!(loaded.unwrap_err().kind(), io::ErrorKind::NotFound);
+        let loaded: Vec<u32> = load_or_default(&path).unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn a_file_that_isnt_the_right_json_is_invalid_data() {
+        let path = temp_file("invalid");
+        fs::write(&path, "{\"ada\": \"three\"}").unwrap();
+        let loaded: io::Result<BTreeMap<String, u32>> = load_or_default(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,490 @@
+// Plays the guessing game as JSON lines, for bots and other programs.
+//
+// Every line in is one request and every request is answered with exactly one
+// line out. Nothing is said unless asked, and no text is meant for people
+// except the message of an error.
+//
+// Requests:
+//     {"type": "new_game"}
+//         Starts a round with the default settings. Any of "difficulty",
+//         "low", "high", "max_guesses" and "seed" can be given to change them;
+//         the range and guess limit work like --min, --max and --max-guesses.
+//         Answered with {"type": "game", ...} holding the round's settings.
+//     {"type": "guess", "value": 50}
+//         The value can also be a string, which is read like typed input.
+//         Answered with {"type": "feedback", "result": "too_small", ...},
+//         where result is too_small, too_big or win, followed by the state.
+//         Rounds with hints also get {"hint": {"band": "warm", "trend": "closer"}},
+//         band being burning, warm or cold and trend closer, farther, same or
+//         null after the first guess.
+//     {"type": "status"}
+//         Answered with {"type": "status", ...} with the state of the round.
+//     {"type": "quit"}
+//         Answered with {"type": "bye"}, then nothing more is read.
+//
+// The state of a round is its "status" (playing, won or lost), the "guesses"
+// used, the "guesses_left" (null without a limit) and, once it is over, the
+// "secret". A request that can't be done is answered with
+//     {"type": "error", "code": "...", "message": "..."}
+// with a code of bad_request, no_game, not_a_number, negative, out_of_range,
+// repeated or game_over.
+//
+// A session could look like this (> in, < out):
+//     > {"type": "new_game", "seed": 42}
+//     < {"type":"game","difficulty":"normal","low":1,"high":100,"max_guesses":7,"seed":42}
+//     > {"type": "guess", "value": 50}
+//     < {"type":"feedback","result":"too_big","status":"playing","guesses":1,"guesses_left":6,"secret":null}
+//     > {"type": "quit"}
+//     < {"type":"bye"}
+
+use crate::difficulty::{Difficulty, Settings};
+use crate::game::{Feedback, Game, Status};
+use crate::hint::Hint;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    NewGame {
+        difficulty: Option<Difficulty>,
+        low: Option<u32>,
+        high: Option<u32>,
+        max_guesses: Option<u32>,
+        seed: Option<u64>,
+    },
+    Guess {
+        value: GuessValue,
+    },
+    Status,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum GuessValue {
+    Number(i64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Game {
+        difficulty: Difficulty,
+        low: u32,
+        high: u32,
+        max_guesses: Option<u32>,
+        seed: u64,
+    },
+    Feedback {
+        result: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        hint: Option<Hint>,
+        #[serde(flatten)]
+        state: State,
+    },
+    Status {
+        #[serde(flatten)]
+        state: State,
+    },
+    Error {
+        code: &'static str,
+        message: String,
+    },
+    Bye,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct State {
+    pub status: &'static str,
+    pub guesses: u32,
+    pub guesses_left: Option<u32>,
+    // Only given away once the round is over.
+    pub secret: Option<u32>,
+}
+
+impl State {
+    fn of(game: &Game) -> State {
+        State {
+            status: match game.status() {
+                Status::Playing => "playing",
+                Status::Won => "won",
+                Status::Lost => "lost",
+            },
+            guesses: game.guesses(),
+            guesses_left: game.guesses_left(),
+            secret: if game.is_over() {
+                Some(game.secret())
+            } else {
+                None
+            },
+        }
+    }
+}
+
+// One client's rounds. Rounds started without a seed of their own use
+// seed + n for the n-th round when a seed is given, or a random one.
+pub struct Session {
+    settings: Settings,
+    seed: Option<u64>,
+    rounds: u64,
+    game: Option<Game>,
+}
+
+impl Session {
+    pub fn new(settings: Settings, seed: Option<u64>) -> Session {
+        Session {
+            settings,
+            seed,
+            rounds: 0,
+            game: None,
+        }
+    }
+
+    // Answers one line of input.
+    pub fn answer(&mut self, line: &str) -> Response {
+        match serde_json::from_str(line) {
+            Ok(request) => self.request(request),
+            Err(e) => Response::Error {
+                code: "bad_request",
+                message: e.to_string(),
+            },
+        }
+    }
+
+    pub fn request(&mut self, request: Request) -> Response {
+        match request {
+            Request::NewGame {
+                difficulty,
+                low,
+                high,
+                max_guesses,
+                seed,
+            } => match self.settings_for(difficulty, low, high, max_guesses) {
+                Ok(settings) => self.new_game(settings, seed),
+                Err(message) => Response::Error {
+                    code: "bad_request",
+                    message,
+                },
+            },
+            Request::Guess { value } => {
+                let game = match self.game.as_mut() {
+                    Some(game) => game,
+                    None => return no_game(),
+                };
+                let input = match value {
+                    GuessValue::Number(number) => number.to_string(),
+                    GuessValue::Text(text) => text,
+                };
+                match game.submit_guess(&input) {
+                    Feedback::Invalid(reason) => Response::Error {
+                        code: reason.code(),
+                        message: reason.to_string(),
+                    },
+                    feedback => Response::Feedback {
+                        result: match feedback {
+                            Feedback::TooSmall => "too_small",
+                            Feedback::TooBig => "too_big",
+                            _ => "win",
+                        },
+                        hint: game.hint(),
+                        state: State::of(game),
+                    },
+                }
+            }
+            Request::Status => match self.game.as_ref() {
+                Some(game) => Response::Status {
+                    state: State::of(game),
+                },
+                None => no_game(),
+            },
+            Request::Quit => Response::Bye,
+        }
+    }
+
+    // The default settings, changed by what a new_game request asks for.
+    // Whether invalid guesses count and whether there are hints stays as it is.
+    fn settings_for(
+        &self,
+        difficulty: Option<Difficulty>,
+        low: Option<u32>,
+        high: Option<u32>,
+        max_guesses: Option<u32>,
+    ) -> Result<Settings, String> {
+        let base = match difficulty {
+            Some(difficulty) => Settings::preset(difficulty),
+            None => self.settings,
+        };
+        let mut settings = if low.is_none() && high.is_none() && max_guesses.is_none() {
+            base
+        } else {
+            Settings::custom(
+                low.unwrap_or(base.low),
+                high.unwrap_or(base.high),
+                max_guesses.or(base.max_guesses),
+            )?
+        };
+
+        settings.count_invalid = self.settings.count_invalid;
+        // Hint bands only fit the range they were made for.
+        let same_range = settings.low == self.settings.low && settings.high == self.settings.high;
+        settings.hints = match self.settings.hints {
+            Some(bands) if same_range => Some(bands),
+            Some(_) => Some(settings.default_hint_bands()),
+            None => None,
+        };
+        Ok(settings)
+    }
+
+    fn new_game(&mut self, settings: Settings, seed: Option<u64>) -> Response {
+        let seed = seed.unwrap_or_else(|| match self.seed {
+            Some(seed) => seed.wrapping_add(self.rounds),
+            None => rand::thread_rng().gen(),
+        });
+        self.rounds += 1;
+        self.game = Some(Game::from_seed(settings, seed));
+
+        Response::Game {
+            difficulty: settings.difficulty,
+            low: settings.low,
+            high: settings.high,
+            max_guesses: settings.max_guesses,
+            seed,
+        }
+    }
+}
+
+fn no_game() -> Response {
+    Response::Error {
+        code: "no_game",
+        message: String::from("start a round with new_game first"),
+    }
+}
+
+// Answers requests from the reader until it closes or the client quits.
+// Blank lines are skipped.
+pub fn run<R, W>(reader: R, mut writer: W, settings: Settings, seed: Option<u64>) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut session = Session::new(settings, seed);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = session.answer(&line);
+        let json = serde_json::to_string(&response).expect("responses are plain data");
+        writeln!(writer, "{}", json)?;
+        writer.flush()?;
+
+        if response == Response::Bye {
+            break;
+        }
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hint::HintBands;
+    use serde_json::{json, Value};
+
+    // Runs a whole session over the lines given and returns the replies.
+    fn replies(settings: Settings, seed: Option<u64>, input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output, settings, seed).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn normal() -> Settings {
+        Settings::preset(Difficulty::Normal)
+    }
+
+    #[test]
+    fn the_sample_session_goes_as_documented() {
+        let input = concat!(
+            "{\"type\": \"new_game\", \"seed\": 42}\n",
+            "{\"type\": \"guess\", \"value\": 50}\n",
+            "{\"type\": \"quit\"}\n",
+        );
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output, normal(), None).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "{\"type\":\"game\",\"difficulty\":\"normal\",\"low\":1,\"high\":100,\"max_guesses\":7,\"seed\":42}\n",
+                "{\"type\":\"feedback\",\"result\":\"too_big\",\"status\":\"playing\",\"guesses\":1,\"guesses_left\":6,\"secret\":null}\n",
+                "{\"type\":\"bye\"}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn a_round_is_played_to_the_end_and_gives_the_secret_away() {
+        let secret = Game::from_seed(normal(), 9).secret();
+        let input = format!(
+            "{}\n\n{}\n{}\n{}\n",
+            json!({"type": "new_game", "seed": 9}),
+            json!({"type": "status"}),
+            json!({"type": "guess", "value": secret.to_string()}),
+            json!({"type": "guess", "value": secret}),
+        );
+        let replies = replies(normal(), None, &input);
+        // The blank line gets no answer.
+        assert_eq!(replies.len(), 4);
+        assert_eq!(replies[1]["type"], "status");
+        assert_eq!(replies[1]["secret"], Value::Null);
+        assert_eq!(replies[2]["result"], "win");
+        assert_eq!(replies[2]["status"], "won");
+        assert_eq!(replies[2]["secret"], secret);
+        assert_eq!(replies[3]["code"], "game_over");
+    }
+
+    #[test]
+    fn requests_that_cant_be_read_are_bad_requests() {
+        let input = concat!(
+            "not json\n",
+            "{\"type\": \"dance\"}\n",
+            "{\"type\": \"guess\"}\n",
+            "{\"type\": \"new_game\", \"low\": 50, \"high\": 10}\n",
+            "{\"type\": \"new_game\", \"difficulty\": \"impossible\"}\n",
+        );
+        for reply in replies(normal(), None, input) {
+            assert_eq!(reply["type"], "error");
+            assert_eq!(reply["code"], "bad_request");
+            assert!(!reply["message"].as_str().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn guesses_and_status_need_a_round() {
+        let input = "{\"type\": \"guess\", \"value\": 5}\n{\"type\": \"status\"}\n";
+        for reply in replies(normal(), None, input) {
+            assert_eq!(reply["type"], "error");
+            assert_eq!(reply["code"], "no_game");
+        }
+    }
+
+    #[test]
+    fn invalid_guesses_get_their_code() {
+        let input = concat!(
+            "{\"type\": \"new_game\", \"low\": 5, \"high\": 6, \"seed\": 0}\n",
+            "{\"type\": \"guess\", \"value\": \"five\"}\n",
+            "{\"type\": \"guess\", \"value\": -5}\n",
+            "{\"type\": \"guess\", \"value\": 7}\n",
+        );
+        let codes: Vec<Value> = replies(normal(), None, input)[1..]
+            .iter()
+            .map(|reply| reply["code"].clone())
+            .collect();
+        assert_eq!(codes, ["not_a_number", "negative", "out_of_range"]);
+    }
+
+    #[test]
+    fn nothing_is_read_after_quit() {
+        let input = "{\"type\": \"quit\"}\n{\"type\": \"new_game\"}\nnot json\n";
+        assert_eq!(replies(normal(), None, input), [json!({"type": "bye"})]);
+    }
+
+    #[test]
+    fn rounds_without_a_seed_count_up_from_the_sessions() {
+        let input = "{\"type\": \"new_game\"}\n{\"type\": \"new_game\", \"seed\": 3}\n{\"type\": \"new_game\"}\n";
+        let seeds: Vec<Value> = replies(normal(), Some(100), input)
+            .iter()
+            .map(|reply| reply["seed"].clone())
+            .collect();
+        assert_eq!(seeds, [100, 3, 102]);
+    }
+
+    #[test]
+    fn new_game_changes_only_what_it_asks_for() {
+        let mut defaults = normal();
+        defaults.count_invalid = true;
+        let session = Session::new(defaults, None);
+
+        let easy = session
+            .settings_for(Some(Difficulty::Easy), None, None, None)
+            .unwrap();
+        assert_eq!((easy.low, easy.high, easy.max_guesses), (1, 50, Some(10)));
+        assert!(easy.count_invalid);
+
+        // A custom round starts from the defaults.
+        let custom = session.settings_for(None, None, Some(10), None).unwrap();
+        assert_eq!(custom.difficulty, Difficulty::Custom);
+        assert_eq!(
+            (custom.low, custom.high, custom.max_guesses),
+            (1, 10, Some(7))
+        );
+        assert!(custom.count_invalid);
+
+        // Or from the preset asked for.
+        let custom = session
+            .settings_for(Some(Difficulty::Hard), Some(500), None, Some(3))
+            .unwrap();
+        assert_eq!(
+            (custom.low, custom.high, custom.max_guesses),
+            (500, 1000, Some(3))
+        );
+
+        assert!(session.settings_for(None, Some(10), Some(5), None).is_err());
+    }
+
+    #[test]
+    fn hint_bands_are_kept_only_for_the_range_they_were_made_for() {
+        let bands = HintBands {
+            burning: 1,
+            warm: 2,
+        };
+        let mut defaults = normal();
+        defaults.hints = Some(bands);
+        let session = Session::new(defaults, None);
+
+        let same = session.settings_for(None, None, None, Some(3)).unwrap();
+        assert_eq!(same.hints, Some(bands));
+
+        let easy = session
+            .settings_for(Some(Difficulty::Easy), None, None, None)
+            .unwrap();
+        assert_eq!(easy.hints, Some(easy.default_hint_bands()));
+
+        let wider = session.settings_for(None, None, Some(1000), None).unwrap();
+        assert_eq!(wider.hints, Some(wider.default_hint_bands()));
+
+        // No hints stays no hints.
+        let session = Session::new(normal(), None);
+        let easy = session
+            .settings_for(Some(Difficulty::Easy), None, None, None)
+            .unwrap();
+        assert_eq!(easy.hints, None);
+    }
+
+    #[test]
+    fn rounds_with_hints_send_them_with_the_feedback() {
+        let mut defaults = normal();
+        defaults.hints = Some(HintBands {
+            burning: 1,
+            warm: 2,
+        });
+        let secret = Game::from_seed(defaults, 5).secret();
+        let far = if secret > 50 { 1 } else { 100 };
+        let input = format!(
+            "{}\n{}\n",
+            json!({"type": "new_game", "seed": 5}),
+            json!({"type": "guess", "value": far}),
+        );
+        let replies = replies(defaults, None, &input);
+        assert_eq!(replies[1]["hint"], json!({"band": "cold", "trend": null}));
+    }
+}
@@ -0,0 +1,30 @@
+// The guessing game engine.
+// Nothing in here reads from stdin or prints to stdout, so the same Game can be
+// driven by the command line binary, tests or any other front end.
+
+pub mod clock;
+pub mod console;
+pub mod daily;
+pub mod date;
+pub mod difficulty;
+pub mod domain;
+pub mod game;
+pub mod hint;
+pub mod hotseat;
+pub mod json_file;
+pub mod json_lines;
+pub mod mastermind;
+pub mod messages;
+pub mod rating;
+pub mod reverse;
+pub mod scores;
+pub mod server;
+pub mod solver;
+pub mod stats;
+pub mod transcript;
+
+pub use difficulty::{Difficulty, Settings};
+pub use game::{Feedback, Game, InvalidGuess, Status};
+pub use hint::{Hint, HintBands};
+pub use messages::{Lang, Message};
+pub use scores::{Score, ScoreBoard};
@@ -1,37 +1,935 @@
+mod cli;
+mod tui;
+
+use cli::{
+    BenchOptions, Command, DailyOptions, HotseatOptions, JsonOptions, MastermindOptions, Options,
+    ReplayOptions, ReverseOptions, ScoresOptions, SecretKind, ServeOptions, StatsOptions,
+};
+use guessing_game::clock::{Clock, TimeLimits};
+use guessing_game::console;
+use guessing_game::daily;
+use guessing_game::domain::{self, Colors, Domain, DomainFeedback, DomainGame, Words};
+use guessing_game::hotseat::{Match, Round};
+use guessing_game::json_lines;
+use guessing_game::mastermind::{self, Mastermind, Solver, FOUR_DIGIT_BOUND};
+use guessing_game::rating::{Change, Rating, Ratings};
+use guessing_game::reverse::{Answer, Outcome, Reverse};
+use guessing_game::server;
+use guessing_game::solver::{self, STRATEGIES};
+use guessing_game::stats::{binary_search_guesses, Stats};
+use guessing_game::transcript::{EventKind, Recorder, Transcript};
+use guessing_game::{
+    Difficulty, Feedback, Game, Lang, Message, Score, ScoreBoard, Settings, Status,
+};
 use rand::Rng;
-use std::cmp::Ordering;
-use std::io;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 fn main() {
-    println!("Guess the number!");
+    match cli::parse(env::args().skip(1)) {
+        Ok(Command::Play(options)) => play(&options),
+        Ok(Command::Scores(options)) => show_scores(&options),
+        Ok(Command::Bench(options)) => bench(&options),
+        Ok(Command::Reverse(options)) => reverse(&options),
+        Ok(Command::Hotseat(options)) => hotseat(&options),
+        Ok(Command::Serve(options)) => serve(&options),
+        Ok(Command::Json(options)) => json_lines(&options),
+        Ok(Command::Replay(options)) => replay(&options),
+        Ok(Command::Stats(options)) => show_stats(&options),
+        Ok(Command::Daily(options)) => play_daily(&options),
+        Ok(Command::Mastermind(options)) => play_mastermind(&options),
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            process::exit(2);
+        }
+    }
+}
 
-    let secret_number = rand::thread_rng().gen_range(1, 101);
+fn play(options: &Options) {
+    let settings = if options.adaptive {
+        adaptive_settings(options)
+    } else {
+        options.settings
+    };
 
-    //println!("The secret number is: {}", secret_number);
+    // Every round is driven by a seed so that it can be replayed later.
+    // Without --seed a fresh one is drawn from the thread-local generator.
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    match options.secret {
+        SecretKind::Number => {}
+        SecretKind::Word(ref path) => return play_domain(options, load_words(path), seed),
+        SecretKind::Date(dates) => return play_domain(options, dates, seed),
+        SecretKind::Color => return play_domain(options, Colors, seed),
+    }
 
-    loop {
-        println!("Please input your guess.");
+    let mut game = Game::from_seed(settings, seed);
+
+    if options.tui {
+        play_tui(options, &mut game, seed);
+        return;
+    }
+
+    // With --record everything said on both sides also goes into a transcript.
+    let mut recorder = options
+        .record
+        .as_ref()
+        .map(|_| Recorder::new(settings, seed, options.lang));
+    let mut say = |kind: EventKind, text: &str| {
+        if kind != EventKind::Guess {
+            println!("{}", text);
+        }
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(kind, text);
+        }
+    };
+
+    let lang = options.lang;
+    for line in console::banner(lang, &settings) {
+        say(EventKind::Prompt, &line);
+    }
+
+    //println!("The secret number is: {}", game.secret());
+
+    // With --auto or --strategy a bot does the typing.
+    let mut bot = options.strategy.as_ref().map(|name| {
+        let mut bot = solver::strategy(name, seed).expect("strategy names are checked by cli");
+        bot.reset(settings.low, settings.high);
+        bot
+    });
+
+    // With a time limit stdin is read on another thread, so that the deadline
+    // can pass while the player is still thinking.
+    let mut clock = Clock::start(options.time_limits);
+    let lines = if options.time_limits.is_set() && bot.is_none() {
+        Some(stdin_lines())
+    } else {
+        None
+    };
+    for line in time_limit_lines(lang, &options.time_limits) {
+        say(EventKind::Prompt, &line);
+    }
+
+    while !game.is_over() {
+        say(EventKind::Prompt, &lang.text(Message::Prompt));
+        clock.start_guess();
+
+        let mut guess = String::new();
+
+        if let Some(bot) = bot.as_mut() {
+            guess = bot.next_guess().to_string();
+        } else if let Some(lines) = lines.as_ref() {
+            match read_before(lines, clock.deadline()) {
+                Ok(line) => guess = line,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            let read = io::stdin()
+                .read_line(&mut guess)
+                .expect("Failed to read line");
+
+            // Stop instead of spinning forever when stdin has been closed.
+            if read == 0 {
+                break;
+            }
+        }
+
+        if let Some(expired) = clock.expired() {
+            game.time_out();
+            say(EventKind::Prompt, &lang.expired(expired));
+            say(EventKind::Prompt, &lang.text(Message::Lose(game.secret())));
+            break;
+        }
+
+        let guess = guess.trim_end_matches(&['\r', '\n'][..]);
+        say(EventKind::Guess, guess);
+
+        let feedback = game.submit_guess(guess);
+
+        if let (Some(bot), Ok(guess)) = (bot.as_mut(), guess.parse()) {
+            bot.observe(guess, feedback);
+        }
+
+        for line in console::responses(lang, &game, guess, feedback) {
+            say(EventKind::Response, &line);
+        }
+
+        if let (false, Some(left)) = (game.is_over(), clock.round_left()) {
+            say(
+                EventKind::Prompt,
+                &lang.text(Message::TimeLeft(left.as_secs_f64())),
+            );
+        }
+    }
+
+    if let (Some(path), Some(recorder)) = (options.record.as_ref(), recorder) {
+        if let Err(e) = recorder.finish().save(path) {
+            eprintln!(
+                "warning: could not save transcript to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    // Bots don't belong on the leaderboard.
+    if game.is_over() && bot.is_none() {
+        record_score(options, &game, clock.elapsed().as_millis() as u64);
+        if options.adaptive {
+            update_rating(options, &game);
+        }
+    }
+
+    if options.show_seed {
+        println!("{}", lang.text(Message::Seed(seed)));
+    }
+
+    if game.status() != Status::Won {
+        process::exit(1);
+    }
+}
+
+// Tells the player how much time they have, if the round is timed.
+fn time_limit_lines(lang: Lang, limits: &TimeLimits) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(round) = limits.round {
+        lines.push(lang.text(Message::RoundTime(round.as_secs())));
+    }
+    if let Some(guess) = limits.guess {
+        lines.push(lang.text(Message::GuessTime(guess.as_secs())));
+    }
+    lines
+}
+
+// Reads stdin on its own thread and hands over the lines as they come in.
+// The channel is closed when stdin is.
+fn stdin_lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            // Nobody is listening any more once the round is over.
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// Waits for the next line, but no longer than the deadline.
+fn read_before(
+    lines: &Receiver<String>,
+    deadline: Option<Instant>,
+) -> Result<String, RecvTimeoutError> {
+    match deadline {
+        Some(deadline) => lines.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        None => lines.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
+
+// Plays a round over words, dates or colors. These aren't recorded on the
+// leaderboard, which compares numeric rounds only.
+fn play_domain<D: Domain>(options: &Options, domain: D, seed: u64) {
+    let max_guesses = if options.limit_given {
+        options.settings.max_guesses
+    } else {
+        Some(domain::guess_limit(&domain, options.settings.difficulty))
+    };
+    let mut game = DomainGame::from_seed(domain, max_guesses, seed);
+
+    let lang = options.lang;
+    println!("{}", lang.text(Message::SecretTitle));
+    let kind = lang.text(game.domain().describe());
+    println!("{}", lang.text(Message::SecretKind(&kind)));
+    if let Some(max) = max_guesses {
+        println!("{}", lang.text(Message::GuessLimit(max)));
+    }
+
+    while !game.is_over() {
+        println!("{}", lang.text(Message::Prompt));
 
         let mut guess = String::new();
 
-        io::stdin()
+        let read = io::stdin()
             .read_line(&mut guess)
             .expect("Failed to read line");
 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
+        if read == 0 {
+            break;
+        }
+
+        let feedback = game.submit_guess(&guess);
+        println!("{}", lang.domain_feedback(&feedback));
+        if let DomainFeedback::Invalid(_) = feedback {
+            continue;
+        }
+
+        if game.status() == Status::Lost {
+            let secret = game.secret().to_string();
+            println!("{}", lang.text(Message::SecretLose(&secret)));
+        } else if let (Status::Playing, Some(left)) = (game.status(), game.guesses_left()) {
+            println!("{}", lang.text(Message::GuessesLeft(left)));
+        }
+    }
+
+    if options.show_seed {
+        println!("{}", lang.text(Message::Seed(seed)));
+    }
+
+    if game.status() != Status::Won {
+        process::exit(1);
+    }
+}
+
+fn load_words(path: &Option<PathBuf>) -> Words {
+    let path = match path {
+        Some(path) => path,
+        None => return Words::default(),
+    };
+
+    let words = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| Words::new(contents.lines()));
+    match words {
+        Ok(words) => words,
+        Err(e) => {
+            eprintln!("error: could not load words from {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn play_tui(options: &Options, game: &mut Game, seed: u64) {
+    let lang = options.lang;
+    let elapsed = match tui::play(game, lang) {
+        Ok(elapsed) => elapsed,
+        Err(e) => {
+            eprintln!("error: the terminal UI failed: {}", e);
+            process::exit(1);
+        }
+    };
+
+    match game.status() {
+        Status::Won => {}
+        Status::Lost => println!("{}", lang.text(Message::Lose(game.secret()))),
+        Status::Playing => println!("{}", lang.text(Message::QuitEarly)),
+    }
+    if game.is_over() {
+        record_score(options, game, elapsed.as_millis() as u64);
+        if options.adaptive {
+            update_rating(options, game);
+        }
+    }
+
+    if options.show_seed {
+        println!("{}", lang.text(Message::Seed(seed)));
+    }
+
+    if game.status() != Status::Won {
+        process::exit(1);
+    }
+}
+
+// The round for the player's level. The other round options still apply,
+// with hint bands to fit the level's range.
+fn adaptive_settings(options: &Options) -> Settings {
+    let rating = match Ratings::load(&options.rating_file) {
+        Ok(ratings) => ratings.get(&options.player),
+        Err(e) => {
+            eprintln!(
+                "warning: could not read {}, starting from the first level: {}",
+                options.rating_file.display(),
+                e
+            );
+            Rating::default()
+        }
+    };
+
+    let mut settings = rating.settings();
+    settings.count_invalid = options.settings.count_invalid;
+    if options.settings.hints.is_some() {
+        settings.hints = Some(settings.default_hint_bands());
+    }
+
+    println!(
+        "{}",
+        options.lang.text(Message::Level {
+            level: rating.level,
+            low: settings.low,
+            high: settings.high,
+            max_guesses: settings.max_guesses.unwrap_or(0),
+        })
+    );
+    settings
+}
+
+// Moves the player's level along after a finished round.
+fn update_rating(options: &Options, game: &Game) {
+    let result = Ratings::load(&options.rating_file).and_then(|mut ratings| {
+        let rating = ratings.player_mut(&options.player);
+        let change = rating.record(game.status() == Status::Won);
+        let level = rating.level;
+        ratings.save(&options.rating_file)?;
+        Ok((change, level))
+    });
+
+    match result {
+        Ok((Change::Up, level)) => println!("{}", options.lang.text(Message::LevelUp(level))),
+        Ok((Change::Down, level)) => println!("{}", options.lang.text(Message::LevelDown(level))),
+        Ok((Change::Same, _)) => {}
+        Err(e) => eprintln!(
+            "warning: could not save level to {}: {}",
+            options.rating_file.display(),
+            e
+        ),
+    }
+}
+
+// Adds a finished round to the score file. Failing to save a score shouldn't
+// spoil the round, so problems are only reported.
+fn record_score(options: &Options, game: &Game, elapsed_ms: u64) {
+    let settings = game.settings();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+
+    let result = ScoreBoard::load(&options.score_file).and_then(|mut board| {
+        board.add(Score {
+            player: options.player.clone(),
+            difficulty: settings.difficulty,
+            low: settings.low,
+            high: settings.high,
+            max_guesses: settings.max_guesses,
+            guesses: game.guesses(),
+            elapsed_ms,
+            timestamp,
+            won: game.status() == Status::Won,
+        });
+        board.save(&options.score_file)
+    });
+
+    match result {
+        Ok(()) if game.status() != Status::Won => {}
+        Ok(()) => println!(
+            "{}",
+            options.lang.text(Message::Won {
+                player: &options.player,
+                guesses: game.guesses(),
+                seconds: elapsed_ms as f64 / 1000.0,
+            })
+        ),
+        Err(e) => eprintln!(
+            "warning: could not save score to {}: {}",
+            options.score_file.display(),
+            e
+        ),
+    }
+}
+
+// Prints the top ten of every leaderboard that has scores, or of just the
+// ones of one difficulty.
+fn show_scores(options: &ScoresOptions) {
+    let board = match ScoreBoard::load(&options.score_file) {
+        Ok(board) => board,
+        Err(e) => {
+            eprintln!(
+                "error: could not read {}: {}",
+                options.score_file.display(),
+                e
+            );
+            process::exit(1);
+        }
+    };
+
+    let difficulties = match options.difficulty {
+        Some(difficulty) => vec![difficulty],
+        None => Difficulty::ALL.to_vec(),
+    };
+
+    let lang = options.lang;
+    let mut shown = false;
+    for difficulty in difficulties {
+        for leaderboard in board.leaderboards(difficulty) {
+            if shown {
+                println!();
+            }
+            shown = true;
+
+            println!("{}", lang.text(Message::ScoresTitle(leaderboard.rules)));
+            println!("{}", lang.text(Message::ScoresHeader));
+            for (rank, score) in leaderboard.scores.iter().take(10).enumerate() {
+                println!(
+                    "{:>4}  {:<16} {:>7} {:>8.1}s  {}-{}",
+                    rank + 1,
+                    score.player,
+                    score.guesses,
+                    score.elapsed_ms as f64 / 1000.0,
+                    score.low,
+                    score.high
+                );
+            }
+        }
+    }
+
+    if !shown {
+        println!("{}", lang.text(Message::NoScores));
+    }
+}
+
+// Plays the same rounds with every strategy and compares how many guesses
+// each one needed.
+fn bench(options: &BenchOptions) {
+    let settings = options.settings;
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let size = u64::from(settings.high - settings.low) + 1;
+
+    let lang = options.lang;
+    println!(
+        "{}",
+        lang.text(Message::BenchTitle {
+            games: options.games,
+            low: settings.low,
+            high: settings.high,
+            seed,
+        })
+    );
+    println!(
+        "{}",
+        lang.text(Message::BenchBound(binary_search_guesses(size)))
+    );
+    println!();
+    println!("{}", lang.text(Message::BenchHeader));
+
+    for name in STRATEGIES.iter() {
+        let mut strategy = solver::strategy(name, seed).expect("listed strategies exist");
+        let result = solver::benchmark(strategy.as_mut(), settings, options.games, seed);
+        println!(
+            "{:<8}  {:>12.2}  {:>11}",
+            result.strategy, result.mean_guesses, result.max_guesses
+        );
+    }
+}
+
+// The computer guesses and the player answers.
+fn reverse(options: &ReverseOptions) {
+    let settings = options.settings;
+    let lang = options.lang;
+    println!(
+        "{}",
+        lang.text(Message::ReverseIntro {
+            low: settings.low,
+            high: settings.high,
+        })
+    );
+
+    let mut computer = Reverse::new(settings.low, settings.high);
+
+    loop {
+        let guess = computer.next_guess();
+        println!("{}", lang.text(Message::ReverseAsk(guess)));
+
+        let mut answer = String::new();
+
+        let read = io::stdin()
+            .read_line(&mut answer)
+            .expect("Failed to read line");
+
+        if read == 0 {
+            process::exit(1);
+        }
+
+        let answer = match Answer::parse(&answer) {
+            Some(answer) => answer,
+            None => {
+                println!("{}", lang.text(Message::ReverseAnswer));
+                continue;
+            }
         };
 
-        println!("You guessed: {}", guess);
+        match computer.answer(answer) {
+            Ok(Outcome::Continue) => {}
+            Ok(Outcome::Found { number, guesses }) => {
+                println!("{}", lang.text(Message::ReverseFound { number, guesses }));
+                break;
+            }
+            Err(contradiction) => {
+                println!("{}", lang.text(Message::Cheating(contradiction)));
+                process::exit(1);
+            }
+        }
+    }
+}
+
+// Local multiplayer: the players share the keyboard and take turns.
+fn hotseat(options: &HotseatOptions) {
+    let settings = options.settings;
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut score = Match::new(options.players.clone(), options.best_of);
+
+    let lang = options.lang;
+    println!("{}", lang.text(Message::Title));
+    println!(
+        "{}",
+        lang.text(Message::HotseatIntro {
+            players: options.players.len(),
+            best_of: options.best_of,
+            low: settings.low,
+            high: settings.high,
+        })
+    );
+
+    while !score.is_over() {
+        let round_seed = seed.wrapping_add(u64::from(score.played()) * 1000);
+        let mut round = Round::new(options.players.len(), settings, round_seed, options.shared);
+
+        println!();
+        println!("{}", lang.text(Message::RoundNumber(score.played() + 1)));
+
+        while !round.is_over() {
+            let player = round.current_player();
+            let name = &options.players[player];
+            println!("{}", lang.text(Message::PlayerPrompt(name)));
+
+            let mut guess = String::new();
+
+            let read = io::stdin()
+                .read_line(&mut guess)
+                .expect("Failed to read line");
+
+            if read == 0 {
+                process::exit(1);
+            }
+
+            let feedback = round.submit_guess(&guess);
+            if let Feedback::Invalid(_) = feedback {
+                println!("{}", lang.feedback(&feedback));
+            } else {
+                println!(
+                    "{}",
+                    lang.text(Message::PlayerGuessed {
+                        player: name,
+                        guess: guess.trim(),
+                        feedback: &lang.feedback(&feedback),
+                    })
+                );
+                if let Some(hint) = round.game(player).hint() {
+                    println!("{}", lang.hint(&hint));
+                }
+            }
+
+            if round.game(player).status() == Status::Lost {
+                println!("{}", lang.text(Message::OutOfGuesses(name)));
+            }
+        }
+
+        print_round_summary(lang, &round, &options.players);
+
+        let winner = round.winner();
+        match winner {
+            Some(player) => println!(
+                "{}",
+                lang.text(Message::RoundWinner(&options.players[player]))
+            ),
+            None => println!("{}", lang.text(Message::NobodyWins)),
+        }
+        score.record(winner);
+
+        let standings: Vec<String> = score
+            .players()
+            .iter()
+            .zip(score.wins())
+            .map(|(name, wins)| format!("{} {}", name, wins))
+            .collect();
+        println!("{}", lang.text(Message::Standings(&standings.join(", "))));
+    }
+
+    println!();
+    match score.leader() {
+        Some(player) => println!(
+            "{}",
+            lang.text(Message::MatchWinner(&options.players[player]))
+        ),
+        None => println!("{}", lang.text(Message::MatchDraw)),
+    }
+}
+
+fn print_round_summary(lang: Lang, round: &Round, players: &[String]) {
+    println!();
+    println!("{}", lang.text(Message::SummaryHeader));
+    for (player, name) in players.iter().enumerate() {
+        let game = round.game(player);
+        let result = match game.status() {
+            Status::Won => lang.text(Message::FoundIt),
+            Status::Lost => lang.text(Message::LostItWas(game.secret())),
+            Status::Playing => String::from("-"),
+        };
+        println!("{:<16} {:>7}  {}", name, game.guesses(), result);
+    }
+    if round.is_shared() {
+        println!("{}", lang.text(Message::SecretWas(round.game(0).secret())));
+    }
+}
+
+fn serve(options: &ServeOptions) {
+    let listener = match TcpListener::bind((options.host.as_str(), options.port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "error: could not listen on {}:{}: {}",
+                options.host, options.port, e
+            );
+            process::exit(1);
+        }
+    };
+
+    if let Ok(address) = listener.local_addr() {
+        println!("{}", options.lang.text(Message::Listening(address)));
+    }
+
+    if let Err(e) = server::serve(listener, options.settings, options.seed) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn json_lines(options: &JsonOptions) {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(e) = json_lines::run(stdin.lock(), stdout.lock(), options.settings, options.seed) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+// Plays a recorded transcript against the engine and checks nothing changed.
+fn replay(options: &ReplayOptions) {
+    let path = &options.path;
+    let transcript = match Transcript::load(path) {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            eprintln!("error: could not read {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    match transcript.replay() {
+        Ok(guesses) => println!(
+            "{}",
+            options.lang.text(Message::ReplayMatches {
+                guesses,
+                seed: transcript.seed,
+            })
+        ),
+        Err(mismatch) => {
+            eprint!("Transcript differs: {}", mismatch);
+            process::exit(1);
+        }
+    }
+}
 
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
+fn show_stats(options: &StatsOptions) {
+    let board = match ScoreBoard::load(&options.score_file) {
+        Ok(board) => board,
+        Err(e) => {
+            eprintln!(
+                "error: could not read {}: {}",
+                options.score_file.display(),
+                e
+            );
+            process::exit(1);
+        }
+    };
+
+    let stats = Stats::new(&board.scores, options.difficulty, options.player.as_deref());
+
+    if options.json {
+        let json = serde_json::to_string_pretty(&stats).expect("stats are plain data");
+        println!("{}", json);
+        return;
+    }
+
+    let optional = |value: Option<f64>| match value {
+        Some(value) => format!("{:.2}", value),
+        None => String::from("-"),
+    };
+
+    let lang = options.lang;
+    println!("{}", lang.text(Message::GamesPlayed(stats.games)));
+    println!("{}", lang.text(Message::GamesWon(stats.wins)));
+    println!("{}", lang.text(Message::WinRate(stats.win_rate * 100.0)));
+    let mean = optional(stats.mean_guesses);
+    println!("{}", lang.text(Message::MeanGuesses(&mean)));
+    let median = optional(stats.median_guesses);
+    println!("{}", lang.text(Message::MedianGuesses(&median)));
+
+    if !stats.histogram.is_empty() {
+        println!();
+        println!("{}", lang.text(Message::HistogramHeader));
+        let most = stats.histogram.values().copied().max().unwrap_or(1);
+        for (guesses, wins) in &stats.histogram {
+            let bar = "#".repeat((wins * 40 / most).max(1) as usize);
+            println!("{:>7}  {:>5}  {}", guesses, wins, bar);
+        }
+    }
+
+    if !stats.players.is_empty() {
+        println!();
+        println!("{}", lang.text(Message::PlayersHeader));
+        for player in &stats.players {
+            println!(
+                "{:<16} {:>6}  {:>8.1}%  {:>6}  {:>6}  {:>7}  {:>12}",
+                player.player,
+                player.games,
+                player.win_rate * 100.0,
+                optional(player.mean_guesses),
+                optional(player.median_guesses),
+                optional(player.mean_optimal),
+                optional(player.mean_over_optimal)
+            );
+        }
+    }
+}
+
+// Today's challenge, line by line like a normal round.
+fn play_daily(options: &DailyOptions) {
+    let mut game = daily::game(options.date, options.difficulty);
+
+    let lang = options.lang;
+    println!("{}", lang.text(Message::DailyTitle(options.date)));
+    for line in console::banner(lang, game.settings()) {
+        println!("{}", line);
+    }
+
+    while !game.is_over() {
+        println!("{}", lang.text(Message::Prompt));
+
+        let mut guess = String::new();
+
+        let read = io::stdin()
+            .read_line(&mut guess)
+            .expect("Failed to read line");
+
+        if read == 0 {
+            break;
+        }
+
+        let feedback = game.submit_guess(&guess);
+        for line in console::responses(lang, &game, &guess, feedback) {
+            println!("{}", line);
+        }
+    }
+
+    if !game.is_over() {
+        process::exit(1);
+    }
+
+    println!();
+    println!("{}", daily::share(options.date, &game));
+
+    if game.status() != Status::Won {
+        process::exit(1);
+    }
+}
+
+// Bulls and cows: crack a code of distinct digits.
+fn play_mastermind(options: &MastermindOptions) {
+    let lang = options.lang;
+    if options.bench {
+        bench_mastermind(lang, options.digits);
+        return;
+    }
+
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut game = Mastermind::from_seed(options.digits, options.max_guesses, seed);
+
+    println!("{}", lang.text(Message::CodeTitle));
+    println!("{}", lang.text(Message::CodeDigits(game.length())));
+    println!("{}", lang.text(Message::CodeRules));
+
+    let mut bot = if options.auto {
+        Some(Solver::new(game.length()))
+    } else {
+        None
+    };
+
+    while !game.is_over() {
+        println!("{}", lang.text(Message::Prompt));
+
+        let mut guess = String::new();
+
+        if let Some(bot) = bot.as_ref() {
+            guess = bot.next_guess().to_string();
+            println!("{}", guess);
+        } else {
+            let read = io::stdin()
+                .read_line(&mut guess)
+                .expect("Failed to read line");
+
+            if read == 0 {
                 break;
             }
         }
+
+        let feedback = game.submit_guess(&guess);
+        println!("{}", lang.code_feedback(feedback));
+        if let mastermind::Feedback::Invalid(_) = feedback {
+            continue;
+        }
+        if let (Some(bot), Some((code, score))) = (bot.as_mut(), game.history().last()) {
+            bot.observe(code, *score);
+        }
+
+        if game.status() == Status::Lost {
+            let code = game.secret().to_string();
+            println!("{}", lang.text(Message::CodeLose(&code)));
+        } else if let (Status::Playing, Some(left)) = (game.status(), game.guesses_left()) {
+            println!("{}", lang.text(Message::GuessesLeft(left)));
+        }
+    }
+
+    if game.status() == Status::Won {
+        println!("{}", lang.text(Message::CrackedIn(game.guesses())));
+    }
+
+    if options.show_seed {
+        println!("{}", lang.text(Message::Seed(seed)));
+    }
+
+    if game.status() != Status::Won {
+        process::exit(1);
+    }
+}
+
+// Plays the solver against every code of the given length.
+fn bench_mastermind(lang: Lang, digits: usize) {
+    let started = Instant::now();
+    let (worst, mean) = mastermind::solver_performance(digits);
+
+    println!("{}", lang.text(Message::CodeBenchTitle(digits)));
+    println!("{}", lang.text(Message::CodeBenchMean(mean)));
+    println!("{}", lang.text(Message::CodeBenchMax(worst)));
+    if digits == 4 {
+        println!("{}", lang.text(Message::CodeBenchBound(FOUR_DIGIT_BOUND)));
+    }
+    println!(
+        "{}",
+        lang.text(Message::Took(started.elapsed().as_secs_f64()))
+    );
+
+    if digits == 4 && worst > FOUR_DIGIT_BOUND {
+        process::exit(1);
     }
 }
@@ -0,0 +1,486 @@
+// A code-breaking variant of the game, better known as bulls and cows.
+//
+// The secret is a code of distinct digits. Every guess is answered with the
+// number of bulls (right digit in the right place) and cows (right digit in
+// the wrong place) instead of too small or too big.
+
+use crate::game::Status;
+use crate::messages::{Lang, Message};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+
+pub const MIN_DIGITS: usize = 1;
+pub const MAX_DIGITS: usize = 6;
+// solver_performance walks the whole decision tree, which takes about half a
+// minute for five digits with optimizations and far longer for six.
+pub const MAX_BENCH_DIGITS: usize = 5;
+
+// The most guesses the solver needs for four digits, measured with
+// `mastermind --bench` (5.385 on average). Seven is also the best any
+// strategy can guarantee for the classic four digit game.
+pub const FOUR_DIGIT_BOUND: u32 = 7;
+
+// A code of distinct digits, leading zeros allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Code {
+    digits: Vec<u8>,
+    // Bit d is set when digit d is in the code.
+    mask: u16,
+}
+
+impl Code {
+    pub fn new(digits: Vec<u8>) -> Option<Code> {
+        let mut mask = 0u16;
+        for &digit in &digits {
+            if digit > 9 || mask & (1 << digit) != 0 {
+                return None;
+            }
+            mask |= 1 << digit;
+        }
+        Some(Code { digits, mask })
+    }
+
+    // Reads a code of `length` distinct digits.
+    pub fn parse(input: &str, length: usize) -> Result<Code, InvalidCode> {
+        let input = input.trim();
+        if input.len() != length || !input.chars().all(|c| c.is_ascii_digit()) {
+            return Err(InvalidCode::NotDigits(length));
+        }
+
+        let digits = input.bytes().map(|b| b - b'0').collect();
+        Code::new(digits).ok_or(InvalidCode::RepeatedDigit)
+    }
+
+    pub fn len(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // How this code scores as a guess against `secret`.
+    pub fn score(&self, secret: &Code) -> Score {
+        let bulls = self
+            .digits
+            .iter()
+            .zip(&secret.digits)
+            .filter(|(a, b)| a == b)
+            .count() as u32;
+        let common = (self.mask & secret.mask).count_ones();
+        Score {
+            bulls,
+            cows: common - bulls,
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for digit in &self.digits {
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+// A code of `length` digits to show how one is typed, like 0123.
+pub fn example(length: usize) -> String {
+    (0..length)
+        .map(|digit| char::from(b'0' + digit as u8))
+        .collect()
+}
+
+// Every code with `length` distinct digits, in increasing order.
+pub fn all_codes(length: usize) -> Vec<Code> {
+    fn extend(code: &mut Vec<u8>, length: usize, codes: &mut Vec<Code>) {
+        if code.len() == length {
+            codes.push(Code::new(code.clone()).expect("digits are distinct"));
+            return;
+        }
+        for digit in 0..10 {
+            if !code.contains(&digit) {
+                code.push(digit);
+                extend(code, length, codes);
+                code.pop();
+            }
+        }
+    }
+
+    let mut codes = Vec::new();
+    extend(&mut Vec::new(), length, &mut codes);
+    codes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Score {
+    pub bulls: u32,
+    pub cows: u32,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.text(Message::CodeScore(*self)))
+    }
+}
+
+// Why a guess wasn't taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidCode {
+    // Not this many digits and nothing else.
+    NotDigits(usize),
+    RepeatedDigit,
+    // A code of another length than the secret's.
+    WrongLength(usize),
+    GameOver,
+}
+
+impl fmt::Display for InvalidCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.invalid_code(*self))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feedback {
+    Score(Score),
+    Win,
+    // The input wasn't a code. It doesn't use up a guess.
+    Invalid(InvalidCode),
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.code_feedback(*self))
+    }
+}
+
+// A round of bulls and cows.
+#[derive(Debug, Clone)]
+pub struct Mastermind {
+    secret: Code,
+    max_guesses: Option<u32>,
+    history: Vec<(Code, Score)>,
+    status: Status,
+}
+
+impl Mastermind {
+    pub fn new(secret: Code, max_guesses: Option<u32>) -> Mastermind {
+        Mastermind {
+            secret,
+            max_guesses,
+            history: Vec::new(),
+            status: Status::Playing,
+        }
+    }
+
+    // Picks a secret of `length` digits with a seeded generator.
+    pub fn from_seed(length: usize, max_guesses: Option<u32>, seed: u64) -> Mastermind {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut digits: Vec<u8> = (0..10).collect();
+        rng.shuffle(&mut digits);
+        digits.truncate(length);
+        Mastermind::new(
+            Code::new(digits).expect("shuffled digits are distinct"),
+            max_guesses,
+        )
+    }
+
+    pub fn submit_guess(&mut self, input: &str) -> Feedback {
+        if self.status != Status::Playing {
+            return Feedback::Invalid(InvalidCode::GameOver);
+        }
+        match Code::parse(input, self.secret.len()) {
+            Ok(guess) => self.guess(guess),
+            Err(reason) => Feedback::Invalid(reason),
+        }
+    }
+
+    pub fn guess(&mut self, guess: Code) -> Feedback {
+        if self.status != Status::Playing {
+            return Feedback::Invalid(InvalidCode::GameOver);
+        }
+        if guess.len() != self.secret.len() {
+            return Feedback::Invalid(InvalidCode::WrongLength(self.secret.len()));
+        }
+
+        let score = guess.score(&self.secret);
+        self.history.push((guess, score));
+
+        if score.bulls as usize == self.secret.len() {
+            self.status = Status::Won;
+            return Feedback::Win;
+        }
+        if let Some(max) = self.max_guesses {
+            if self.guesses() >= max {
+                self.status = Status::Lost;
+            }
+        }
+        Feedback::Score(score)
+    }
+
+    pub fn secret(&self) -> &Code {
+        &self.secret
+    }
+
+    pub fn length(&self) -> usize {
+        self.secret.len()
+    }
+
+    pub fn guesses(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn guesses_left(&self) -> Option<u32> {
+        self.max_guesses
+            .map(|max| max.saturating_sub(self.guesses()))
+    }
+
+    pub fn history(&self) -> &[(Code, Score)] {
+        &self.history
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != Status::Playing
+    }
+}
+
+// Above this many score computations per guess the solver stops looking for
+// the best split and just plays a code that could still be the secret.
+const MINIMAX_BUDGET: usize = 50_000_000;
+
+// Plays bulls and cows with Knuth's minimax method: every guess is the code
+// whose worst possible answer leaves the fewest candidates, preferring codes
+// that could still be the secret.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    codes: Vec<Code>,
+    candidates: Vec<Code>,
+}
+
+impl Solver {
+    pub fn new(length: usize) -> Solver {
+        let codes = all_codes(length);
+        Solver {
+            candidates: codes.clone(),
+            codes,
+        }
+    }
+
+    pub fn next_guess(&self) -> Code {
+        best_guess(&self.codes, &self.candidates)
+    }
+
+    // Drops every candidate that wouldn't have given this answer.
+    pub fn observe(&mut self, guess: &Code, score: Score) {
+        self.candidates.retain(|code| guess.score(code) == score);
+    }
+
+    // The codes that are still possible.
+    pub fn candidates(&self) -> &[Code] {
+        &self.candidates
+    }
+}
+
+fn best_guess(codes: &[Code], candidates: &[Code]) -> Code {
+    // Before any answer every code splits the others the same way, so the
+    // first code is as good an opening as any.
+    if candidates.len() <= 2
+        || candidates.len() == codes.len()
+        || codes.len() * candidates.len() > MINIMAX_BUDGET
+    {
+        return candidates[0].clone();
+    }
+
+    let mut best: Option<(usize, bool, &Code)> = None;
+    for guess in codes {
+        // How many candidates give each answer, indexed by bulls and cows.
+        let mut sizes = [0usize; (MAX_DIGITS + 1) * (MAX_DIGITS + 1)];
+        for candidate in candidates {
+            let score = guess.score(candidate);
+            sizes[score.bulls as usize * (MAX_DIGITS + 1) + score.cows as usize] += 1;
+        }
+        let worst = sizes.iter().copied().max().unwrap_or(0);
+        let possible = candidates.contains(guess);
+
+        let better = match best {
+            None => true,
+            Some((best_worst, best_possible, _)) => {
+                worst < best_worst || (worst == best_worst && possible && !best_possible)
+            }
+        };
+        if better {
+            best = Some((worst, possible, guess));
+        }
+    }
+
+    best.expect("there is at least one code").2.clone()
+}
+
+// Lets the solver play the round to the end.
+pub fn solve(game: &mut Mastermind) {
+    let mut solver = Solver::new(game.length());
+    while !game.is_over() {
+        let guess = solver.next_guess();
+        if let Feedback::Score(score) = game.guess(guess.clone()) {
+            solver.observe(&guess, score);
+        }
+    }
+}
+
+// The most guesses the solver ever needs for codes of `length` digits, and the
+// mean over every possible secret. The solver's choices only depend on the
+// answers so far, so this walks its decision tree once instead of playing
+// every secret separately.
+pub fn solver_performance(length: usize) -> (u32, f64) {
+    let codes = all_codes(length);
+    let total_codes = codes.len();
+
+    // Each entry is the candidates left and how many guesses were made so far.
+    let mut pending = vec![(codes.clone(), 0u32)];
+    let mut worst = 0;
+    let mut total = 0u64;
+
+    while let Some((candidates, depth)) = pending.pop() {
+        let guess = best_guess(&codes, &candidates);
+        let mut splits: HashMap<Score, Vec<Code>> = HashMap::new();
+        for candidate in candidates {
+            splits
+                .entry(guess.score(&candidate))
+                .or_default()
+                .push(candidate);
+        }
+
+        for (score, codes_left) in splits {
+            if score.bulls as usize == length {
+                worst = worst.max(depth + 1);
+                total += u64::from(depth + 1);
+            } else {
+                pending.push((codes_left, depth + 1));
+            }
+        }
+    }
+
+    (worst, total as f64 / total_codes as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(digits: &str) -> Code {
+        Code::parse(digits, digits.len()).unwrap()
+    }
+
+    #[test]
+    fn bulls_and_cows_are_counted() {
+        let secret = code("1234");
+        let score = |guess| code(guess).score(&secret);
+        assert_eq!(score("1234"), Score { bulls: 4, cows: 0 });
+        assert_eq!(score("4321"), Score { bulls: 0, cows: 4 });
+        assert_eq!(score("1243"), Score { bulls: 2, cows: 2 });
+        assert_eq!(score("5678"), Score { bulls: 0, cows: 0 });
+        assert_eq!(score("0291"), Score { bulls: 1, cows: 1 });
+    }
+
+    #[test]
+    fn only_codes_of_distinct_digits_are_read() {
+        assert!(Code::parse("0123", 4).is_ok());
+        assert!(Code::parse(" 9876\n", 4).is_ok());
+        assert!(Code::parse("1123", 4).is_err());
+        assert!(Code::parse("123", 4).is_err());
+        assert!(Code::parse("12a4", 4).is_err());
+        assert_eq!(Code::new(vec![1, 10]), None);
+    }
+
+    #[test]
+    fn there_are_as_many_codes_as_arrangements_of_digits() {
+        assert_eq!(all_codes(1).len(), 10);
+        assert_eq!(all_codes(3).len(), 720);
+        assert_eq!(all_codes(4).len(), 5040);
+    }
+
+    #[test]
+    fn a_round_is_won_or_lost() {
+        let mut game = Mastermind::new(code("1234"), Some(2));
+        assert_eq!(
+            game.submit_guess("4321"),
+            Feedback::Score(Score { bulls: 0, cows: 4 })
+        );
+        assert!(matches!(game.submit_guess("11"), Feedback::Invalid(_)));
+        assert_eq!(game.guesses(), 1);
+        assert_eq!(game.submit_guess("1234"), Feedback::Win);
+        assert_eq!(game.status(), Status::Won);
+
+        let mut game = Mastermind::new(code("1234"), Some(1));
+        game.submit_guess("5678");
+        assert_eq!(game.status(), Status::Lost);
+        assert!(matches!(game.submit_guess("1234"), Feedback::Invalid(_)));
+    }
+
+    #[test]
+    fn the_same_seed_hides_the_same_code() {
+        for seed in 0..20 {
+            let game = Mastermind::from_seed(4, None, seed);
+            assert_eq!(game.secret().len(), 4);
+            assert_eq!(Mastermind::from_seed(4, None, seed).secret(), game.secret());
+        }
+    }
+
+    #[test]
+    fn solving_every_three_digit_code_matches_the_decision_tree() {
+        // The solver only goes by the answers so far, so its guesses are kept
+        // by the history that led to them. Asking it again for every secret
+        // takes half a minute without optimizations.
+        let mut guesses: HashMap<Vec<(Code, Score)>, Code> = HashMap::new();
+        let mut worst = 0;
+        let mut total = 0;
+        for secret in all_codes(3) {
+            let mut game = Mastermind::new(secret, None);
+            let mut solver = Solver::new(3);
+            while !game.is_over() {
+                let guess = guesses
+                    .entry(game.history().to_vec())
+                    .or_insert_with(|| solver.next_guess())
+                    .clone();
+                if let Feedback::Score(score) = game.guess(guess.clone()) {
+                    solver.observe(&guess, score);
+                }
+            }
+            assert_eq!(game.status(), Status::Won);
+            worst = worst.max(game.guesses());
+            total += game.guesses();
+        }
+
+        let (tree_worst, tree_mean) = solver_performance(3);
+        assert_eq!(worst, tree_worst);
+        assert!((f64::from(total) / 720.0 - tree_mean).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_plays_the_round_to_a_win() {
+        for seed in 0..5 {
+            let mut game = Mastermind::from_seed(2, Some(10), seed);
+            solve(&mut game);
+            assert_eq!(game.status(), Status::Won, "seed {}", seed);
+        }
+    }
+
+    // Walks the solver's whole decision tree for four digits, which is what
+    // mastermind --bench does. It takes half a minute without optimizations,
+    // so it only runs when asked for:
+    //     cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn the_solver_always_finishes_four_digits_within_the_known_bound() {
+        let (worst, mean) = solver_performance(4);
+        assert!(worst <= FOUR_DIGIT_BOUND, "needed {} guesses", worst);
+        assert!(mean < 5.5, "needed {} guesses on average", mean);
+    }
+}
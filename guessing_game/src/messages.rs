@@ -0,0 +1,1267 @@
+// Everything the game says to the player, in every language it speaks.
+//
+// Each message is a variant of Message and every language turns all of them
+// into text with a match that has no catch-all arm, so a message that is
+// missing from a catalog is a compile error rather than a blank line.
+// Errors, warnings and --help stay English, and so do the json and serve
+// protocols and the daily share text, which read the same for everybody.
+
+use crate::clock::Expired;
+use crate::date::Date;
+use crate::difficulty::Difficulty;
+use crate::domain::{Direction, DomainFeedback, DomainInvalid};
+use crate::game::{Feedback, InvalidGuess};
+use crate::hint::{Band, Hint, Trend};
+use crate::mastermind::{self, InvalidCode, Score};
+use crate::reverse::Contradiction;
+use crate::scores::Rules;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::net::SocketAddr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Message<'a> {
+    Title,
+    DifficultyName(Difficulty),
+    Difficulty {
+        difficulty: Difficulty,
+        low: u32,
+        high: u32,
+    },
+    Prompt,
+    YouGuessed(&'a str),
+    TooSmall,
+    TooBig,
+    Win,
+    NotANumber,
+    Negative,
+    OutOfRange {
+        low: u32,
+        high: u32,
+    },
+    Repeated(u32),
+    GameOver,
+    Band(Band),
+    // Starts with a space, it always follows a Band.
+    Trend(Trend),
+    Lose(u32),
+    GuessesLeft(u32),
+    Won {
+        player: &'a str,
+        guesses: u32,
+        seconds: f64,
+    },
+    QuitEarly,
+    Seed(u64),
+    RoundTime(u64),
+    GuessTime(u64),
+    TimeLeft(f64),
+    RoundExpired,
+    GuessExpired,
+    DailyTitle(Date),
+    Level {
+        level: u32,
+        low: u32,
+        high: u32,
+        max_guesses: u32,
+    },
+    LevelUp(u32),
+    LevelDown(u32),
+    // The full-screen round.
+    TuiHelp,
+    TuiWin,
+    TuiLose(u32),
+    TuiTitle {
+        difficulty: Difficulty,
+        low: u32,
+        high: u32,
+    },
+    TuiExactly(u32),
+    TuiBetween {
+        low: u32,
+        high: u32,
+    },
+    TuiGuessesOf {
+        left: u32,
+        max: u32,
+    },
+    TuiUnlimited,
+    TuiStatus {
+        left: &'a str,
+        minutes: u64,
+        seconds: u64,
+    },
+    TuiGuesses,
+    // Rounds over words, dates and colors.
+    SecretTitle,
+    // What the secret is, from one of the Describe messages.
+    SecretKind(&'a str),
+    DescribeWords {
+        count: usize,
+        first: &'a str,
+        last: &'a str,
+    },
+    DescribeDates {
+        first: Date,
+        last: Date,
+    },
+    DescribeColors,
+    GuessLimit(u32),
+    WordHint(Direction),
+    DateHint(Direction),
+    ColorHint([Option<Direction>; 3]),
+    NotInDictionary(&'a str),
+    NotADate(&'a str),
+    DateOutOfRange {
+        first: Date,
+        last: Date,
+    },
+    NotAColor(&'a str),
+    RepeatedSecret(&'a str),
+    SecretLose(&'a str),
+    // Bulls and cows.
+    CodeTitle,
+    CodeDigits(usize),
+    CodeRules,
+    CodeScore(Score),
+    CodeCracked,
+    NotDigits(usize),
+    RepeatedDigit,
+    WrongLength(usize),
+    CodeLose(&'a str),
+    CrackedIn(u32),
+    CodeBenchTitle(usize),
+    CodeBenchMean(f64),
+    CodeBenchMax(u32),
+    CodeBenchBound(u32),
+    Took(f64),
+    // The computer guesses.
+    ReverseIntro {
+        low: u32,
+        high: u32,
+    },
+    ReverseAsk(u32),
+    ReverseAnswer,
+    ReverseFound {
+        number: u32,
+        guesses: u32,
+    },
+    // Lowercase, to go inside Cheating.
+    Contradiction(Contradiction),
+    Cheating(Contradiction),
+    // Local multiplayer.
+    HotseatIntro {
+        players: usize,
+        best_of: u32,
+        low: u32,
+        high: u32,
+    },
+    RoundNumber(u32),
+    PlayerPrompt(&'a str),
+    PlayerGuessed {
+        player: &'a str,
+        guess: &'a str,
+        feedback: &'a str,
+    },
+    OutOfGuesses(&'a str),
+    SummaryHeader,
+    FoundIt,
+    LostItWas(u32),
+    SecretWas(u32),
+    RoundWinner(&'a str),
+    NobodyWins,
+    Standings(&'a str),
+    MatchWinner(&'a str),
+    MatchDraw,
+    // The reports of the subcommands. The table headers line up with the
+    // rows main prints under them.
+    ScoresTitle(Rules),
+    ScoresHeader,
+    NoScores,
+    GamesPlayed(u32),
+    GamesWon(u32),
+    WinRate(f64),
+    MeanGuesses(&'a str),
+    MedianGuesses(&'a str),
+    HistogramHeader,
+    PlayersHeader,
+    BenchTitle {
+        games: u32,
+        low: u32,
+        high: u32,
+        seed: u64,
+    },
+    BenchBound(u32),
+    BenchHeader,
+    Listening(SocketAddr),
+    ReplayMatches {
+        guesses: usize,
+        seed: u64,
+    },
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::English, Lang::German];
+
+    // Takes a language code like "de", or a locale like "de_DE.UTF-8".
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let language = code
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Lang::English),
+            "de" => Some(Lang::German),
+            _ => None,
+        }
+    }
+
+    // The language of the locale, checking LC_ALL, LC_MESSAGES and LANG in the
+    // order POSIX gives them. Anything unknown falls back to English.
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::from_code(&value))
+            .unwrap_or_default()
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::English => "en",
+            Lang::German => "de",
+        }
+    }
+
+    pub fn text(self, message: Message) -> String {
+        match self {
+            Lang::English => english(message),
+            Lang::German => german(message),
+        }
+    }
+
+    pub fn feedback(self, feedback: &Feedback) -> String {
+        match feedback {
+            Feedback::TooSmall => self.text(Message::TooSmall),
+            Feedback::TooBig => self.text(Message::TooBig),
+            Feedback::Win => self.text(Message::Win),
+            Feedback::Invalid(reason) => self.invalid(reason),
+        }
+    }
+
+    pub fn invalid(self, reason: &InvalidGuess) -> String {
+        self.text(match *reason {
+            InvalidGuess::NotANumber => Message::NotANumber,
+            InvalidGuess::Negative => Message::Negative,
+            InvalidGuess::OutOfRange { low, high } => Message::OutOfRange { low, high },
+            InvalidGuess::Repeated(guess) => Message::Repeated(guess),
+            InvalidGuess::GameOver => Message::GameOver,
+        })
+    }
+
+    pub fn hint(self, hint: &Hint) -> String {
+        let mut text = self.text(Message::Band(hint.band));
+        if let Some(trend) = hint.trend {
+            text.push_str(&self.text(Message::Trend(trend)));
+        }
+        text
+    }
+
+    pub fn expired(self, expired: Expired) -> String {
+        self.text(match expired {
+            Expired::Round => Message::RoundExpired,
+            Expired::Guess => Message::GuessExpired,
+        })
+    }
+
+    pub fn domain_feedback<H>(self, feedback: &DomainFeedback<H>) -> String
+    where
+        H: Copy + Into<Message<'static>>,
+    {
+        match feedback {
+            DomainFeedback::Hint(hint) => self.text((*hint).into()),
+            DomainFeedback::Win => self.text(Message::Win),
+            DomainFeedback::Invalid(reason) => self.domain_invalid(reason),
+        }
+    }
+
+    pub fn domain_invalid(self, reason: &DomainInvalid) -> String {
+        self.text(match reason {
+            DomainInvalid::NotInDictionary(input) => Message::NotInDictionary(input),
+            DomainInvalid::NotADate(input) => Message::NotADate(input),
+            DomainInvalid::DateOutOfRange { first, last } => Message::DateOutOfRange {
+                first: *first,
+                last: *last,
+            },
+            DomainInvalid::NotAColor(input) => Message::NotAColor(input),
+            DomainInvalid::Repeated(guess) => Message::RepeatedSecret(guess),
+            DomainInvalid::GameOver => Message::GameOver,
+        })
+    }
+
+    pub fn code_feedback(self, feedback: mastermind::Feedback) -> String {
+        match feedback {
+            mastermind::Feedback::Score(score) => self.text(Message::CodeScore(score)),
+            mastermind::Feedback::Win => self.text(Message::CodeCracked),
+            mastermind::Feedback::Invalid(reason) => self.invalid_code(reason),
+        }
+    }
+
+    pub fn invalid_code(self, reason: InvalidCode) -> String {
+        self.text(match reason {
+            InvalidCode::NotDigits(length) => Message::NotDigits(length),
+            InvalidCode::RepeatedDigit => Message::RepeatedDigit,
+            InvalidCode::WrongLength(length) => Message::WrongLength(length),
+            InvalidCode::GameOver => Message::GameOver,
+        })
+    }
+}
+
+fn english(message: Message) -> String {
+    match message {
+        Message::Title => String::from("Guess the number!"),
+        Message::DifficultyName(difficulty) => difficulty.name().to_string(),
+        Message::Difficulty {
+            difficulty,
+            low,
+            high,
+        } => format!(
+            "Difficulty: {} (between {} and {})",
+            english(Message::DifficultyName(difficulty)),
+            low,
+            high
+        ),
+        Message::Prompt => String::from("Please input your guess."),
+        Message::YouGuessed(input) => format!("You guessed: {}", input),
+        Message::TooSmall => String::from("Too small!"),
+        Message::TooBig => String::from("Too big!"),
+        Message::Win => String::from("You win!"),
+        Message::NotANumber => String::from("That's not a number, please type a number."),
+        Message::Negative => String::from("The secret number is never negative."),
+        Message::OutOfRange { low, high } => {
+            format!("The secret number is between {} and {}.", low, high)
+        }
+        Message::Repeated(guess) => format!("You already guessed {}.", guess),
+        Message::GameOver => String::from("The round is already over."),
+        Message::Band(Band::Burning) => String::from("Burning!"),
+        Message::Band(Band::Warm) => String::from("Warm."),
+        Message::Band(Band::Cold) => String::from("Cold."),
+        Message::Trend(Trend::Closer) => String::from(" Closer than last time."),
+        Message::Trend(Trend::Farther) => String::from(" Further away than last time."),
+        Message::Trend(Trend::Same) => String::from(" Just as far as last time."),
+        Message::Lose(secret) => format!("You lose! The secret number was {}.", secret),
+        Message::GuessesLeft(left) => format!("Guesses left: {}", left),
+        Message::Won {
+            player,
+            guesses,
+            seconds,
+        } => format!(
+            "{} won in {} ({:.1}s).",
+            player,
+            english_guesses(u64::from(guesses)),
+            seconds
+        ),
+        Message::QuitEarly => String::from("Quit before the end."),
+        Message::Seed(seed) => format!("Seed: {}", seed),
+        Message::RoundTime(seconds) => format!("You have {}s for the round.", seconds),
+        Message::GuessTime(seconds) => format!("You have {}s for each guess.", seconds),
+        Message::TimeLeft(seconds) => format!("Time left: {:.1}s", seconds),
+        Message::RoundExpired => String::from("Time's up!"),
+        Message::GuessExpired => String::from("Too slow, time's up for that guess!"),
+        Message::DailyTitle(date) => format!("Daily challenge for {}", date),
+        Message::Level {
+            level,
+            low,
+            high,
+            max_guesses,
+        } => format!(
+            "Level {}: between {} and {}, {}.",
+            level,
+            low,
+            high,
+            english_guesses(u64::from(max_guesses))
+        ),
+        Message::LevelUp(level) => format!("Level up! Your next round is level {}.", level),
+        Message::LevelDown(level) => format!("Level down, your next round is level {}.", level),
+        Message::TuiHelp => String::from("Type a number and press Enter. Esc quits."),
+        Message::TuiWin => String::from("You win! Press any key to exit."),
+        Message::TuiLose(secret) => format!(
+            "You lose! The secret number was {}. Press any key to exit.",
+            secret
+        ),
+        Message::TuiTitle {
+            difficulty,
+            low,
+            high,
+        } => format!(
+            "Guess the number!  ({} difficulty, {} to {})",
+            english(Message::DifficultyName(difficulty)),
+            low,
+            high
+        ),
+        Message::TuiExactly(number) => format!("It is {}", number),
+        Message::TuiBetween { low, high } => format!("It is between {} and {}", low, high),
+        Message::TuiGuessesOf { left, max } => format!("{} of {}", left, max),
+        Message::TuiUnlimited => String::from("unlimited"),
+        Message::TuiStatus {
+            left,
+            minutes,
+            seconds,
+        } => format!(
+            "Guesses left: {:<12} Time: {:02}:{:02}",
+            left, minutes, seconds
+        ),
+        Message::TuiGuesses => String::from("Guesses:"),
+        Message::SecretTitle => String::from("Guess the secret!"),
+        Message::SecretKind(kind) => format!("It is {}.", kind),
+        Message::DescribeWords { count, first, last } => format!(
+            "a word from a dictionary of {}, between '{}' and '{}'",
+            count, first, last
+        ),
+        Message::DescribeDates { first, last } => {
+            format!("a date between {} and {}", first, last)
+        }
+        Message::DescribeColors => String::from("a color between #000000 and #ffffff"),
+        Message::GuessLimit(max) => format!("You have {}.", english_guesses(u64::from(max))),
+        Message::WordHint(Direction::Higher) => {
+            String::from("The secret word comes later in the dictionary.")
+        }
+        Message::WordHint(Direction::Lower) => {
+            String::from("The secret word comes earlier in the dictionary.")
+        }
+        Message::DateHint(Direction::Higher) => String::from("Too early!"),
+        Message::DateHint(Direction::Lower) => String::from("Too late!"),
+        Message::ColorHint(channels) => {
+            let parts: Vec<String> = ["red", "green", "blue"]
+                .iter()
+                .zip(channels.iter())
+                .map(|(name, channel)| {
+                    let verdict = match channel {
+                        Some(Direction::Higher) => "too low",
+                        Some(Direction::Lower) => "too high",
+                        None => "right",
+                    };
+                    format!("{} {}", name, verdict)
+                })
+                .collect();
+            format!("{}.", parts.join(", "))
+        }
+        Message::NotInDictionary(input) => format!("'{}' isn't in the dictionary.", input),
+        Message::NotADate(input) => format!("'{}' is not a date like 2020-10-27.", input),
+        Message::DateOutOfRange { first, last } => {
+            format!("The secret date is between {} and {}.", first, last)
+        }
+        Message::NotAColor(input) => format!("'{}' is not a color like #3a7bd5.", input),
+        Message::RepeatedSecret(guess) => format!("You already guessed {}.", guess),
+        Message::SecretLose(secret) => format!("You lose! The secret was {}.", secret),
+        Message::CodeTitle => String::from("Crack the code!"),
+        Message::CodeDigits(length) => format!("It has {} different digits.", length),
+        Message::CodeRules => String::from(
+            "Bulls are right digits in the right place, cows right digits in the wrong place.",
+        ),
+        Message::CodeScore(Score { bulls, cows }) => {
+            let plural = |n: u32| if n == 1 { "" } else { "s" };
+            format!(
+                "{} bull{}, {} cow{}",
+                bulls,
+                plural(bulls),
+                cows,
+                plural(cows)
+            )
+        }
+        Message::CodeCracked => String::from("You cracked the code!"),
+        Message::NotDigits(length) => format!(
+            "Please type {} digits, like {}.",
+            length,
+            mastermind::example(length)
+        ),
+        Message::RepeatedDigit => String::from("Every digit in the code is different."),
+        Message::WrongLength(length) => format!("The code has {} digits.", length),
+        Message::CodeLose(code) => format!("You lose! The code was {}.", code),
+        Message::CrackedIn(guesses) => {
+            format!("Cracked in {}.", english_guesses(u64::from(guesses)))
+        }
+        Message::CodeBenchTitle(length) => format!("Every code of {} different digits:", length),
+        Message::CodeBenchMean(mean) => format!("Mean guesses:  {:.3}", mean),
+        Message::CodeBenchMax(max) => format!("Max guesses:   {}", max),
+        Message::CodeBenchBound(bound) => format!("Known bound:   {}", bound),
+        Message::Took(seconds) => format!("Took {:.1}s.", seconds),
+        Message::ReverseIntro { low, high } => format!(
+            "Think of a number between {} and {}, I'll guess it!",
+            low, high
+        ),
+        Message::ReverseAsk(guess) => {
+            format!("Is it {}? (h)igher, (l)ower or (c)orrect", guess)
+        }
+        Message::ReverseAnswer => String::from("Please answer h, l or c."),
+        Message::ReverseFound { number, guesses } => format!(
+            "Your number is {}, I got it in {}!",
+            number,
+            english_guesses(u64::from(guesses))
+        ),
+        Message::Contradiction(contradiction) => match contradiction {
+            Contradiction {
+                above: Some(above),
+                below: Some(below),
+                ..
+            } => format!(
+                "you said your number is higher than {} but lower than {}",
+                above, below
+            ),
+            Contradiction {
+                above: Some(above),
+                below: None,
+                high,
+                ..
+            } => format!(
+                "you said your number is higher than {}, but it can't be above {}",
+                above, high
+            ),
+            Contradiction {
+                above: None,
+                below: Some(below),
+                low,
+                ..
+            } => format!(
+                "you said your number is lower than {}, but it can't be below {}",
+                below, low
+            ),
+            Contradiction { low, high, .. } => {
+                format!("no number between {} and {} fits", low, high)
+            }
+        },
+        Message::Cheating(contradiction) => format!(
+            "Hold on, {}. That's cheating!",
+            english(Message::Contradiction(contradiction))
+        ),
+        Message::HotseatIntro {
+            players,
+            best_of,
+            low,
+            high,
+        } => format!(
+            "{} players, best of {}, numbers between {} and {}.",
+            players, best_of, low, high
+        ),
+        Message::RoundNumber(round) => format!("== Round {} ==", round),
+        Message::PlayerPrompt(player) => format!("{}, please input your guess.", player),
+        Message::PlayerGuessed {
+            player,
+            guess,
+            feedback,
+        } => format!("{} guessed {}: {}", player, guess, feedback),
+        Message::OutOfGuesses(player) => format!("{} is out of guesses.", player),
+        Message::SummaryHeader => format!("{:<16} {:>7}  {}", "Player", "Guesses", "Result"),
+        Message::FoundIt => String::from("found it"),
+        Message::LostItWas(secret) => format!("lost, it was {}", secret),
+        Message::SecretWas(secret) => format!("The secret number was {}.", secret),
+        Message::RoundWinner(player) => format!("{} wins the round!", player),
+        Message::NobodyWins => String::from("Nobody wins this round."),
+        Message::Standings(standings) => format!("Match: {}", standings),
+        Message::MatchWinner(player) => format!("{} wins the match!", player),
+        Message::MatchDraw => String::from("The match is a draw."),
+        Message::ScoresTitle(Rules::Preset(difficulty)) => format!("== {} ==", difficulty),
+        Message::ScoresTitle(Rules::Custom {
+            low,
+            high,
+            max_guesses: Some(max),
+        }) => format!(
+            "== custom {}-{}, {} ==",
+            low,
+            high,
+            english_guesses(u64::from(max))
+        ),
+        Message::ScoresTitle(Rules::Custom {
+            low,
+            high,
+            max_guesses: None,
+        }) => format!("== custom {}-{}, unlimited ==", low, high),
+        Message::ScoresHeader => format!(
+            "{:>4}  {:<16} {:>7} {:>9}  {}",
+            "Rank", "Player", "Guesses", "Time", "Range"
+        ),
+        Message::NoScores => String::from("No scores yet."),
+        Message::GamesPlayed(games) => format!("Games played:   {}", games),
+        Message::GamesWon(wins) => format!("Games won:      {}", wins),
+        Message::WinRate(rate) => format!("Win rate:       {:.1}%", rate),
+        Message::MeanGuesses(mean) => format!("Mean guesses:   {}", mean),
+        Message::MedianGuesses(median) => format!("Median guesses: {}", median),
+        Message::HistogramHeader => format!("{:>7}  {:>5}", "Guesses", "Wins"),
+        Message::PlayersHeader => format!(
+            "{:<16} {:>6}  {:>9}  {:>6}  {:>6}  {:>7}  {:>12}",
+            "Player", "Games", "Win rate", "Mean", "Median", "Optimal", "Over optimal"
+        ),
+        Message::BenchTitle {
+            games,
+            low,
+            high,
+            seed,
+        } => format!(
+            "{} games between {} and {} (seed {})",
+            games, low, high, seed
+        ),
+        Message::BenchBound(guesses) => {
+            format!(
+                "A binary search never needs more than {}.",
+                english_guesses(u64::from(guesses))
+            )
+        }
+        Message::BenchHeader => format!(
+            "{:<8}  {:>12}  {:>11}",
+            "Strategy", "Mean guesses", "Max guesses"
+        ),
+        Message::Listening(address) => format!("Guessing game listening on {}", address),
+        Message::ReplayMatches { guesses, seed } => format!(
+            "Transcript matches: {} replayed with seed {}.",
+            english_guesses(guesses as u64),
+            seed
+        ),
+    }
+}
+
+fn german(message: Message) -> String {
+    match message {
+        Message::Title => String::from("Errate die Zahl!"),
+        Message::DifficultyName(difficulty) => String::from(match difficulty {
+            Difficulty::Easy => "leicht",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "schwer",
+            Difficulty::Custom => "eigene",
+        }),
+        Message::Difficulty {
+            difficulty,
+            low,
+            high,
+        } => format!(
+            "Schwierigkeit: {} (zwischen {} und {})",
+            german(Message::DifficultyName(difficulty)),
+            low,
+            high
+        ),
+        Message::Prompt => String::from("Bitte gib deinen Tipp ein."),
+        Message::YouGuessed(input) => format!("Dein Tipp: {}", input),
+        Message::TooSmall => String::from("Zu klein!"),
+        Message::TooBig => String::from("Zu groß!"),
+        Message::Win => String::from("Gewonnen!"),
+        Message::NotANumber => String::from("Das ist keine Zahl, bitte gib eine Zahl ein."),
+        Message::Negative => String::from("Die geheime Zahl ist nie negativ."),
+        Message::OutOfRange { low, high } => {
+            format!("Die geheime Zahl liegt zwischen {} und {}.", low, high)
+        }
+        Message::Repeated(guess) => format!("{} hast du schon geraten.", guess),
+        Message::GameOver => String::from("Die Runde ist schon vorbei."),
+        Message::Band(Band::Burning) => String::from("Heiß!"),
+        Message::Band(Band::Warm) => String::from("Warm."),
+        Message::Band(Band::Cold) => String::from("Kalt."),
+        Message::Trend(Trend::Closer) => String::from(" Näher dran als beim letzten Mal."),
+        Message::Trend(Trend::Farther) => String::from(" Weiter weg als beim letzten Mal."),
+        Message::Trend(Trend::Same) => String::from(" Genauso weit weg wie beim letzten Mal."),
+        Message::Lose(secret) => format!("Verloren! Die geheime Zahl war {}.", secret),
+        Message::GuessesLeft(left) => format!("Verbleibende Tipps: {}", left),
+        Message::Won {
+            player,
+            guesses,
+            seconds,
+        } => format!(
+            "{} hat mit {} gewonnen ({:.1}s).",
+            player,
+            german_guesses(u64::from(guesses)),
+            seconds
+        ),
+        Message::QuitEarly => String::from("Vor dem Ende aufgehört."),
+        Message::Seed(seed) => format!("Seed: {}", seed),
+        Message::RoundTime(seconds) => format!("Du hast {}s für die Runde.", seconds),
+        Message::GuessTime(seconds) => format!("Du hast {}s für jeden Tipp.", seconds),
+        Message::TimeLeft(seconds) => format!("Verbleibende Zeit: {:.1}s", seconds),
+        Message::RoundExpired => String::from("Die Zeit ist um!"),
+        Message::GuessExpired => String::from("Zu langsam, die Zeit für diesen Tipp ist um!"),
+        Message::DailyTitle(date) => format!("Tagesaufgabe für {}", date),
+        Message::Level {
+            level,
+            low,
+            high,
+            max_guesses,
+        } => format!(
+            "Stufe {}: zwischen {} und {}, {}.",
+            level,
+            low,
+            high,
+            german_guesses(u64::from(max_guesses))
+        ),
+        Message::LevelUp(level) => {
+            format!("Aufgestiegen! Deine nächste Runde ist Stufe {}.", level)
+        }
+        Message::LevelDown(level) => {
+            format!("Abgestiegen, deine nächste Runde ist Stufe {}.", level)
+        }
+        Message::TuiHelp => String::from("Gib eine Zahl ein und drücke Enter. Esc beendet."),
+        Message::TuiWin => String::from("Gewonnen! Beliebige Taste zum Beenden."),
+        Message::TuiLose(secret) => format!(
+            "Verloren! Die geheime Zahl war {}. Beliebige Taste zum Beenden.",
+            secret
+        ),
+        Message::TuiTitle {
+            difficulty,
+            low,
+            high,
+        } => format!(
+            "Errate die Zahl!  (Schwierigkeit {}, {} bis {})",
+            german(Message::DifficultyName(difficulty)),
+            low,
+            high
+        ),
+        Message::TuiExactly(number) => format!("Sie ist {}", number),
+        Message::TuiBetween { low, high } => format!("Sie liegt zwischen {} und {}", low, high),
+        Message::TuiGuessesOf { left, max } => format!("{} von {}", left, max),
+        Message::TuiUnlimited => String::from("unbegrenzt"),
+        Message::TuiStatus {
+            left,
+            minutes,
+            seconds,
+        } => format!(
+            "Tipps übrig: {:<12} Zeit: {:02}:{:02}",
+            left, minutes, seconds
+        ),
+        Message::TuiGuesses => String::from("Tipps:"),
+        Message::SecretTitle => String::from("Errate das Geheimnis!"),
+        Message::SecretKind(kind) => format!("Gesucht ist {}.", kind),
+        Message::DescribeWords { count, first, last } => format!(
+            "ein Wort aus einem Wörterbuch mit {} Wörtern, zwischen '{}' und '{}'",
+            count, first, last
+        ),
+        Message::DescribeDates { first, last } => {
+            format!("ein Datum zwischen {} und {}", first, last)
+        }
+        Message::DescribeColors => String::from("eine Farbe zwischen #000000 und #ffffff"),
+        Message::GuessLimit(max) => format!("Du hast {}.", german_guesses(u64::from(max))),
+        Message::WordHint(Direction::Higher) => {
+            String::from("Das geheime Wort kommt später im Wörterbuch.")
+        }
+        Message::WordHint(Direction::Lower) => {
+            String::from("Das geheime Wort kommt früher im Wörterbuch.")
+        }
+        Message::DateHint(Direction::Higher) => String::from("Zu früh!"),
+        Message::DateHint(Direction::Lower) => String::from("Zu spät!"),
+        Message::ColorHint(channels) => {
+            let parts: Vec<String> = ["Rot", "Grün", "Blau"]
+                .iter()
+                .zip(channels.iter())
+                .map(|(name, channel)| {
+                    let verdict = match channel {
+                        Some(Direction::Higher) => "zu niedrig",
+                        Some(Direction::Lower) => "zu hoch",
+                        None => "richtig",
+                    };
+                    format!("{} {}", name, verdict)
+                })
+                .collect();
+            format!("{}.", parts.join(", "))
+        }
+        Message::NotInDictionary(input) => format!("'{}' steht nicht im Wörterbuch.", input),
+        Message::NotADate(input) => format!("'{}' ist kein Datum wie 2020-10-27.", input),
+        Message::DateOutOfRange { first, last } => {
+            format!("Das geheime Datum liegt zwischen {} und {}.", first, last)
+        }
+        Message::NotAColor(input) => format!("'{}' ist keine Farbe wie #3a7bd5.", input),
+        Message::RepeatedSecret(guess) => format!("{} hast du schon geraten.", guess),
+        Message::SecretLose(secret) => format!("Verloren! Das Geheimnis war {}.", secret),
+        Message::CodeTitle => String::from("Knack den Code!"),
+        Message::CodeDigits(length) => format!("Er hat {} verschiedene Ziffern.", length),
+        Message::CodeRules => String::from(
+            "Bullen sind richtige Ziffern an der richtigen Stelle, Kühe richtige Ziffern an der falschen.",
+        ),
+        Message::CodeScore(Score { bulls, cows }) => format!(
+            "{} {}, {} {}",
+            bulls,
+            if bulls == 1 { "Bulle" } else { "Bullen" },
+            cows,
+            if cows == 1 { "Kuh" } else { "Kühe" }
+        ),
+        Message::CodeCracked => String::from("Du hast den Code geknackt!"),
+        Message::NotDigits(length) => format!(
+            "Bitte gib {} Ziffern ein, etwa {}.",
+            length,
+            mastermind::example(length)
+        ),
+        Message::RepeatedDigit => String::from("Jede Ziffer im Code ist anders."),
+        Message::WrongLength(length) => format!("Der Code hat {} Ziffern.", length),
+        Message::CodeLose(code) => format!("Verloren! Der Code war {}.", code),
+        Message::CrackedIn(guesses) => {
+            format!("Mit {} geknackt.", german_guesses(u64::from(guesses)))
+        }
+        Message::CodeBenchTitle(length) => {
+            format!("Jeder Code aus {} verschiedenen Ziffern:", length)
+        }
+        Message::CodeBenchMean(mean) => format!("Tipps im Mittel:  {:.3}", mean),
+        Message::CodeBenchMax(max) => format!("Höchstens Tipps:  {}", max),
+        Message::CodeBenchBound(bound) => format!("Bekannte Grenze:  {}", bound),
+        Message::Took(seconds) => format!("Dauerte {:.1}s.", seconds),
+        Message::ReverseIntro { low, high } => format!(
+            "Denk dir eine Zahl zwischen {} und {}, ich errate sie!",
+            low, high
+        ),
+        Message::ReverseAsk(guess) => format!(
+            "Ist es {}? h (höher), l (niedriger) oder c (richtig)",
+            guess
+        ),
+        Message::ReverseAnswer => String::from("Bitte antworte mit h, l oder c."),
+        Message::ReverseFound { number, guesses } => format!(
+            "Deine Zahl ist {}, ich hatte sie nach {}!",
+            number,
+            german_guesses(u64::from(guesses))
+        ),
+        Message::Contradiction(contradiction) => match contradiction {
+            Contradiction {
+                above: Some(above),
+                below: Some(below),
+                ..
+            } => format!(
+                "du hast gesagt, deine Zahl ist größer als {}, aber kleiner als {}",
+                above, below
+            ),
+            Contradiction {
+                above: Some(above),
+                below: None,
+                high,
+                ..
+            } => format!(
+                "du hast gesagt, deine Zahl ist größer als {}, aber sie ist höchstens {}",
+                above, high
+            ),
+            Contradiction {
+                above: None,
+                below: Some(below),
+                low,
+                ..
+            } => format!(
+                "du hast gesagt, deine Zahl ist kleiner als {}, aber sie ist mindestens {}",
+                below, low
+            ),
+            Contradiction { low, high, .. } => {
+                format!("keine Zahl zwischen {} und {} passt", low, high)
+            }
+        },
+        Message::Cheating(contradiction) => format!(
+            "Moment, {}. Das ist geschummelt!",
+            german(Message::Contradiction(contradiction))
+        ),
+        Message::HotseatIntro {
+            players,
+            best_of,
+            low,
+            high,
+        } => format!(
+            "{} Spieler, best of {}, Zahlen zwischen {} und {}.",
+            players, best_of, low, high
+        ),
+        Message::RoundNumber(round) => format!("== Runde {} ==", round),
+        Message::PlayerPrompt(player) => format!("{}, bitte gib deinen Tipp ein.", player),
+        Message::PlayerGuessed {
+            player,
+            guess,
+            feedback,
+        } => format!("{} tippt {}: {}", player, guess, feedback),
+        Message::OutOfGuesses(player) => format!("{} hat keine Tipps mehr.", player),
+        Message::SummaryHeader => format!("{:<16} {:>7}  {}", "Spieler", "Tipps", "Ergebnis"),
+        Message::FoundIt => String::from("gefunden"),
+        Message::LostItWas(secret) => format!("verloren, es war {}", secret),
+        Message::SecretWas(secret) => format!("Die geheime Zahl war {}.", secret),
+        Message::RoundWinner(player) => format!("{} gewinnt die Runde!", player),
+        Message::NobodyWins => String::from("Niemand gewinnt diese Runde."),
+        Message::Standings(standings) => format!("Spielstand: {}", standings),
+        Message::MatchWinner(player) => format!("{} gewinnt das Match!", player),
+        Message::MatchDraw => String::from("Das Match endet unentschieden."),
+        Message::ScoresTitle(Rules::Preset(difficulty)) => {
+            format!("== {} ==", german(Message::DifficultyName(difficulty)))
+        }
+        Message::ScoresTitle(Rules::Custom {
+            low,
+            high,
+            max_guesses: Some(max),
+        }) => format!(
+            "== eigene {}-{}, {} ==",
+            low,
+            high,
+            german_guesses(u64::from(max))
+        ),
+        Message::ScoresTitle(Rules::Custom {
+            low,
+            high,
+            max_guesses: None,
+        }) => format!("== eigene {}-{}, unbegrenzt ==", low, high),
+        Message::ScoresHeader => format!(
+            "{:>4}  {:<16} {:>7} {:>9}  {}",
+            "Rang", "Spieler", "Tipps", "Zeit", "Bereich"
+        ),
+        Message::NoScores => String::from("Noch keine Ergebnisse."),
+        Message::GamesPlayed(games) => format!("Runden gespielt:  {}", games),
+        Message::GamesWon(wins) => format!("Runden gewonnen:  {}", wins),
+        Message::WinRate(rate) => format!("Siegquote:        {:.1}%", rate),
+        Message::MeanGuesses(mean) => format!("Tipps im Mittel:  {}", mean),
+        Message::MedianGuesses(median) => format!("Tipps im Median:  {}", median),
+        Message::HistogramHeader => format!("{:>7}  {:>5}", "Tipps", "Siege"),
+        Message::PlayersHeader => format!(
+            "{:<16} {:>6}  {:>9}  {:>6}  {:>6}  {:>7}  {:>12}",
+            "Spieler", "Runden", "Siegquote", "Mittel", "Median", "Optimal", "Über optimal"
+        ),
+        Message::BenchTitle {
+            games,
+            low,
+            high,
+            seed,
+        } => format!(
+            "{} Runden zwischen {} und {} (Seed {})",
+            games, low, high, seed
+        ),
+        Message::BenchBound(guesses) => format!(
+            "Eine binäre Suche braucht nie mehr als {}.",
+            german_guesses(u64::from(guesses))
+        ),
+        Message::BenchHeader => format!(
+            "{:<8}  {:>12}  {:>11}",
+            "Methode", "Tipps Mittel", "Tipps max."
+        ),
+        Message::Listening(address) => format!("Das Ratespiel wartet auf {}", address),
+        Message::ReplayMatches { guesses, seed } => format!(
+            "Das Protokoll stimmt: {} mit Seed {} nachgespielt.",
+            german_guesses(guesses as u64),
+            seed
+        ),
+    }
+}
+
+// "1 guess" but "7 guesses".
+fn english_guesses(count: u64) -> String {
+    match count {
+        1 => String::from("1 guess"),
+        _ => format!("{} guesses", count),
+    }
+}
+
+fn german_guesses(count: u64) -> String {
+    match count {
+        1 => String::from("1 Tipp"),
+        _ => format!("{} Tipps", count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One of every message, to check each catalog says something for it.
+    fn every_message() -> Vec<Message<'static>> {
+        let date = Date::new(2020, 10, 27).unwrap();
+        let contradiction = Contradiction {
+            above: Some(40),
+            below: Some(41),
+            low: 1,
+            high: 100,
+        };
+        vec![
+            Message::Title,
+            Message::DifficultyName(Difficulty::Hard),
+            Message::Difficulty {
+                difficulty: Difficulty::Normal,
+                low: 1,
+                high: 100,
+            },
+            Message::Prompt,
+            Message::YouGuessed("50"),
+            Message::TooSmall,
+            Message::TooBig,
+            Message::Win,
+            Message::NotANumber,
+            Message::Negative,
+            Message::OutOfRange { low: 1, high: 100 },
+            Message::Repeated(50),
+            Message::GameOver,
+            Message::Band(Band::Burning),
+            Message::Band(Band::Cold),
+            Message::Trend(Trend::Closer),
+            Message::Trend(Trend::Farther),
+            Message::Trend(Trend::Same),
+            Message::Lose(42),
+            Message::GuessesLeft(3),
+            Message::Won {
+                player: "ada",
+                guesses: 4,
+                seconds: 1.5,
+            },
+            Message::QuitEarly,
+            Message::RoundTime(30),
+            Message::GuessTime(10),
+            Message::TimeLeft(12.5),
+            Message::RoundExpired,
+            Message::GuessExpired,
+            Message::DailyTitle(date),
+            Message::Level {
+                level: 2,
+                low: 1,
+                high: 50,
+                max_guesses: 8,
+            },
+            Message::LevelUp(3),
+            Message::LevelDown(1),
+            Message::TuiHelp,
+            Message::TuiWin,
+            Message::TuiLose(42),
+            Message::TuiTitle {
+                difficulty: Difficulty::Easy,
+                low: 1,
+                high: 50,
+            },
+            Message::TuiExactly(42),
+            Message::TuiBetween { low: 1, high: 100 },
+            Message::TuiGuessesOf { left: 3, max: 7 },
+            Message::TuiUnlimited,
+            Message::TuiStatus {
+                left: "3",
+                minutes: 1,
+                seconds: 5,
+            },
+            Message::TuiGuesses,
+            Message::SecretTitle,
+            Message::SecretKind("a color"),
+            Message::DescribeWords {
+                count: 3,
+                first: "apple",
+                last: "pear",
+            },
+            Message::DescribeDates {
+                first: date,
+                last: date,
+            },
+            Message::DescribeColors,
+            Message::GuessLimit(9),
+            Message::WordHint(Direction::Higher),
+            Message::WordHint(Direction::Lower),
+            Message::DateHint(Direction::Higher),
+            Message::DateHint(Direction::Lower),
+            Message::ColorHint([Some(Direction::Higher), None, Some(Direction::Lower)]),
+            Message::NotInDictionary("kiwi"),
+            Message::NotADate("tomorrow"),
+            Message::DateOutOfRange {
+                first: date,
+                last: date,
+            },
+            Message::NotAColor("blue"),
+            Message::RepeatedSecret("apple"),
+            Message::SecretLose("#3a7bd5"),
+            Message::CodeTitle,
+            Message::CodeDigits(4),
+            Message::CodeRules,
+            Message::CodeScore(Score { bulls: 1, cows: 2 }),
+            Message::CodeCracked,
+            Message::NotDigits(4),
+            Message::RepeatedDigit,
+            Message::WrongLength(4),
+            Message::CodeLose("0123"),
+            Message::CrackedIn(5),
+            Message::CodeBenchTitle(4),
+            Message::CodeBenchMean(5.2),
+            Message::CodeBenchMax(7),
+            Message::CodeBenchBound(7),
+            Message::Took(1.5),
+            Message::ReverseIntro { low: 1, high: 100 },
+            Message::ReverseAsk(50),
+            Message::ReverseAnswer,
+            Message::ReverseFound {
+                number: 42,
+                guesses: 6,
+            },
+            Message::Contradiction(contradiction),
+            Message::Contradiction(Contradiction {
+                above: Some(100),
+                below: None,
+                ..contradiction
+            }),
+            Message::Contradiction(Contradiction {
+                above: None,
+                below: Some(1),
+                ..contradiction
+            }),
+            Message::Contradiction(Contradiction {
+                above: None,
+                below: None,
+                ..contradiction
+            }),
+            Message::Cheating(contradiction),
+            Message::HotseatIntro {
+                players: 2,
+                best_of: 3,
+                low: 1,
+                high: 100,
+            },
+            Message::RoundNumber(1),
+            Message::PlayerPrompt("ada"),
+            Message::PlayerGuessed {
+                player: "ada",
+                guess: "50",
+                feedback: "!",
+            },
+            Message::OutOfGuesses("ada"),
+            Message::SummaryHeader,
+            Message::FoundIt,
+            Message::LostItWas(42),
+            Message::SecretWas(42),
+            Message::RoundWinner("ada"),
+            Message::NobodyWins,
+            Message::Standings("ada 1, bob 0"),
+            Message::MatchWinner("ada"),
+            Message::MatchDraw,
+            Message::ScoresTitle(Rules::Preset(Difficulty::Easy)),
+            Message::ScoresTitle(Rules::Custom {
+                low: 1,
+                high: 2,
+                max_guesses: Some(2),
+            }),
+            Message::ScoresTitle(Rules::Custom {
+                low: 1,
+                high: 2,
+                max_guesses: None,
+            }),
+            Message::ScoresHeader,
+            Message::NoScores,
+            Message::GamesPlayed(10),
+            Message::GamesWon(7),
+            Message::WinRate(70.0),
+            Message::MeanGuesses("5.00"),
+            Message::MedianGuesses("5.00"),
+            Message::HistogramHeader,
+            Message::PlayersHeader,
+            Message::BenchTitle {
+                games: 1000,
+                low: 1,
+                high: 100,
+                seed: 7,
+            },
+            Message::BenchBound(7),
+            Message::BenchHeader,
+            Message::Listening(SocketAddr::from(([127, 0, 0, 1], 7878))),
+            Message::ReplayMatches {
+                guesses: 7,
+                seed: 42,
+            },
+        ]
+    }
+
+    // These read the same in English and German.
+    const SHARED: [Message<'static>; 3] = [
+        Message::Seed(42),
+        Message::DifficultyName(Difficulty::Normal),
+        Message::Band(Band::Warm),
+    ];
+
+    #[test]
+    fn every_message_is_in_every_catalog() {
+        for &lang in Lang::ALL.iter() {
+            for message in every_message().into_iter().chain(SHARED) {
+                assert!(
+                    !lang.text(message).trim().is_empty(),
+                    "{:?} has no text for {:?}",
+                    lang,
+                    message
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn german_is_not_english_left_untranslated() {
+        for message in every_message() {
+            assert_ne!(
+                Lang::German.text(message),
+                Lang::English.text(message),
+                "{:?} is the same in both",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn the_display_impls_speak_english() {
+        assert_eq!(
+            crate::domain::ColorHint {
+                channels: [Some(Direction::Higher), None, Some(Direction::Lower)]
+            }
+            .to_string(),
+            "red too low, green right, blue too high."
+        );
+        assert_eq!(Score { bulls: 1, cows: 2 }.to_string(), "1 bull, 2 cows");
+        assert_eq!(
+            Lang::German.text(Message::CodeScore(Score { bulls: 1, cows: 2 })),
+            "1 Bulle, 2 Kühe"
+        );
+        assert_eq!(
+            DomainInvalid::NotInDictionary(String::from("Kiwi")).to_string(),
+            "'Kiwi' isn't in the dictionary."
+        );
+    }
+
+    #[test]
+    fn one_guess_is_singular() {
+        let won = |guesses| Message::Won {
+            player: "ada",
+            guesses,
+            seconds: 1.0,
+        };
+        assert_eq!(Lang::English.text(won(1)), "ada won in 1 guess (1.0s).");
+        assert_eq!(Lang::English.text(won(2)), "ada won in 2 guesses (1.0s).");
+        assert_eq!(
+            Lang::German.text(won(1)),
+            "ada hat mit 1 Tipp gewonnen (1.0s)."
+        );
+        assert_eq!(
+            Lang::German.text(won(2)),
+            "ada hat mit 2 Tipps gewonnen (1.0s)."
+        );
+
+        assert_eq!(
+            Lang::English.text(Message::GuessLimit(1)),
+            "You have 1 guess."
+        );
+        assert_eq!(
+            Lang::English.text(Message::GuessLimit(0)),
+            "You have 0 guesses."
+        );
+        assert_eq!(Lang::German.text(Message::GuessLimit(1)), "Du hast 1 Tipp.");
+        assert_eq!(
+            Lang::German.text(Message::GuessLimit(9)),
+            "Du hast 9 Tipps."
+        );
+        assert_eq!(
+            Lang::English.text(Message::CrackedIn(1)),
+            "Cracked in 1 guess."
+        );
+        assert_eq!(
+            Lang::German.text(Message::ReplayMatches {
+                guesses: 1,
+                seed: 7
+            }),
+            "Das Protokoll stimmt: 1 Tipp mit Seed 7 nachgespielt."
+        );
+    }
+
+    #[test]
+    fn languages_come_from_codes_and_locales() {
+        assert_eq!(Lang::from_code("de_DE.UTF-8"), Some(Lang::German));
+        assert_eq!(Lang::from_code("en-GB"), Some(Lang::English));
+        assert_eq!(Lang::from_code("C"), Some(Lang::English));
+        assert_eq!(Lang::from_code("fr_FR"), None);
+        for &lang in Lang::ALL.iter() {
+            assert_eq!(Lang::from_code(lang.code()), Some(lang));
+        }
+    }
+}
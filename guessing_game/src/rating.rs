@@ -0,0 +1,220 @@
+// Adaptive difficulty: every player has a level that picks the range and the
+// guess limit of their next round, going up as they keep winning and down as
+// they lose.
+
+use crate::difficulty::Settings;
+use crate::json_file;
+use crate::stats::optimal_guesses;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+pub const DEFAULT_RATING_FILE: &str = "guessing_game_ratings.json";
+
+pub const MIN_LEVEL: u32 = 1;
+pub const MAX_LEVEL: u32 = 20;
+// Where newcomers start: 1 to 66 with 11 guesses.
+pub const START_LEVEL: u32 = 5;
+
+// How many of the latest rounds decide whether the level changes.
+const RECENT_ROUNDS: usize = 3;
+
+// One player's level and how their latest rounds at it went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub level: u32,
+    // Whether each round since the level last changed was won, oldest first.
+    pub recent: Vec<bool>,
+    pub rounds: u32,
+}
+
+impl Default for Rating {
+    fn default() -> Rating {
+        Rating {
+            level: START_LEVEL,
+            recent: Vec::new(),
+            rounds: 0,
+        }
+    }
+}
+
+// What a finished round did to the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Up,
+    Down,
+    Same,
+}
+
+impl Rating {
+    // The round for the player's level. The range grows by about 60% a level
+    // and the spare guesses over what a binary search needs shrink from five
+    // to one.
+    pub fn settings(&self) -> Settings {
+        let level = self.level.clamp(MIN_LEVEL, MAX_LEVEL);
+        let high = (10.0 * 1.6f64.powi(level as i32 - 1)).round() as u32;
+        let spare = 5u32.saturating_sub(level / 4).max(1);
+        Settings::custom(1, high, Some(optimal_guesses(1, high) + spare))
+            .expect("every level has a valid range")
+    }
+
+    // Counts a finished round. Winning every one of the latest rounds moves
+    // the level up, losing most of them moves it down.
+    pub fn record(&mut self, won: bool) -> Change {
+        self.rounds += 1;
+        self.recent.push(won);
+        if self.recent.len() > RECENT_ROUNDS {
+            self.recent.remove(0);
+        }
+
+        let losses = self.recent.iter().filter(|&&won| !won).count();
+        let change = if self.recent.len() == RECENT_ROUNDS && losses == 0 {
+            Change::Up
+        } else if losses * 2 > RECENT_ROUNDS {
+            Change::Down
+        } else {
+            Change::Same
+        };
+
+        match change {
+            Change::Up if self.level < MAX_LEVEL => self.level += 1,
+            Change::Down if self.level > MIN_LEVEL => self.level -= 1,
+            _ => return Change::Same,
+        }
+        self.recent.clear();
+        change
+    }
+}
+
+// Every player's rating, kept in a JSON file like the scores.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ratings {
+    pub players: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    // Reads the rating file. A file that doesn't exist yet has no players.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Ratings> {
+        json_file::load_or_default(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        json_file::save(self, path)
+    }
+
+    // The player's rating, a newcomer's if they haven't played yet.
+    pub fn get(&self, player: &str) -> Rating {
+        self.players.get(player).cloned().unwrap_or_default()
+    }
+
+    pub fn player_mut(&mut self, player: &str) -> &mut Rating {
+        self.players.entry(player.to_string()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(level: u32) -> Rating {
+        Rating {
+            level,
+            ..Rating::default()
+        }
+    }
+
+    fn round(rating: &Rating) -> (u32, u32, Option<u32>) {
+        let settings = rating.settings();
+        (settings.low, settings.high, settings.max_guesses)
+    }
+
+    #[test]
+    fn newcomers_start_at_the_start_level() {
+        let rating = Ratings::default().get("ada");
+        assert_eq!(rating, at(START_LEVEL));
+        assert_eq!(round(&rating), (1, 66, Some(11)));
+    }
+
+    #[test]
+    fn the_range_grows_and_the_spare_guesses_shrink() {
+        assert_eq!(round(&at(MIN_LEVEL)), (1, 10, Some(9)));
+        assert_eq!(round(&at(MAX_LEVEL)), (1, 75_558, Some(18)));
+
+        let mut previous = round(&at(MIN_LEVEL));
+        for level in MIN_LEVEL + 1..=MAX_LEVEL {
+            let round = round(&at(level));
+            assert!(round.1 > previous.1, "level {}", level);
+            previous = round;
+        }
+    }
+
+    #[test]
+    fn levels_out_of_bounds_play_like_the_nearest_one() {
+        assert_eq!(round(&at(0)), round(&at(MIN_LEVEL)));
+        assert_eq!(round(&at(MAX_LEVEL + 5)), round(&at(MAX_LEVEL)));
+    }
+
+    #[test]
+    fn three_wins_in_a_row_move_the_level_up() {
+        let mut rating = Rating::default();
+        assert_eq!(rating.record(true), Change::Same);
+        assert_eq!(rating.record(true), Change::Same);
+        assert_eq!(rating.recent, [true, true]);
+        assert_eq!(rating.record(true), Change::Up);
+        assert_eq!(rating.level, START_LEVEL + 1);
+        assert!(rating.recent.is_empty());
+        assert_eq!(rating.rounds, 3);
+    }
+
+    #[test]
+    fn losing_two_of_the_last_three_moves_the_level_down() {
+        let mut rating = Rating::default();
+        assert_eq!(rating.record(true), Change::Same);
+        assert_eq!(rating.record(false), Change::Same);
+        assert_eq!(rating.record(false), Change::Down);
+        assert_eq!(rating.level, START_LEVEL - 1);
+        assert!(rating.recent.is_empty());
+
+        // Two losses straight away are already most of three.
+        let mut rating = Rating::default();
+        rating.record(false);
+        assert_eq!(rating.record(false), Change::Down);
+    }
+
+    #[test]
+    fn only_the_last_three_rounds_count() {
+        let mut rating = Rating::default();
+        for won in [true, true, false, true, true] {
+            assert_eq!(rating.record(won), Change::Same);
+        }
+        assert_eq!(rating.recent, [false, true, true]);
+        assert_eq!(rating.record(true), Change::Up);
+        assert_eq!(rating.rounds, 6);
+    }
+
+    #[test]
+    fn the_level_stays_within_bounds() {
+        let mut rating = at(MAX_LEVEL);
+        for _ in 0..3 {
+            assert_eq!(rating.record(true), Change::Same);
+        }
+        assert_eq!(rating.level, MAX_LEVEL);
+
+        let mut rating = at(MIN_LEVEL);
+        for _ in 0..2 {
+            assert_eq!(rating.record(false), Change::Same);
+        }
+        assert_eq!(rating.level, MIN_LEVEL);
+    }
+
+    #[test]
+    fn players_are_kept_apart() {
+        let mut ratings = Ratings::default();
+        for _ in 0..3 {
+            ratings.player_mut("ada").record(true);
+        }
+        assert_eq!(ratings.get("ada").level, START_LEVEL + 1);
+        assert_eq!(ratings.get("bob"), Rating::default());
+    }
+}
@@ -0,0 +1,281 @@
+use crate::messages::{Lang, Message};
+use std::fmt;
+
+// The player's answer to one of the computer's guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    // The player's number is higher than the guess.
+    Higher,
+    // The player's number is lower than the guess.
+    Lower,
+    Correct,
+}
+
+impl Answer {
+    pub fn parse(input: &str) -> Option<Answer> {
+        match input.trim().to_lowercase().as_str() {
+            "h" | "higher" => Some(Answer::Higher),
+            "l" | "lower" => Some(Answer::Lower),
+            "c" | "correct" => Some(Answer::Correct),
+            _ => None,
+        }
+    }
+}
+
+// What happened after the player answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    // The number hasn't been found yet, ask for another guess.
+    Continue,
+    Found { number: u32, guesses: u32 },
+}
+
+// No number can match all of the player's answers.
+// `above` is the guess the player said their number is higher than and
+// `below` the one it is lower than. None stands for the edge of the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction {
+    pub above: Option<u32>,
+    pub below: Option<u32>,
+    pub low: u32,
+    pub high: u32,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Lang::English.text(Message::Contradiction(*self)))
+    }
+}
+
+// The computer's side of the reverse game: it guesses the number the player
+// is thinking of by binary search, keeping track of which answers narrowed
+// the range so it can point out contradictory ones.
+#[derive(Debug, Clone)]
+pub struct Reverse {
+    low: u32,
+    high: u32,
+    // The candidates still consistent with every answer, as low..=high.
+    from: u32,
+    to: u32,
+    above: Option<u32>,
+    below: Option<u32>,
+    current: Option<u32>,
+    guesses: u32,
+}
+
+impl Reverse {
+    pub fn new(low: u32, high: u32) -> Reverse {
+        Reverse {
+            low,
+            high,
+            from: low,
+            to: high,
+            above: None,
+            below: None,
+            current: None,
+            guesses: 0,
+        }
+    }
+
+    // The computer's next guess. Asking again before an answer repeats it.
+    pub fn next_guess(&mut self) -> u32 {
+        if let Some(guess) = self.current {
+            return guess;
+        }
+
+        let guess = self.from + (self.to - self.from) / 2;
+        self.current = Some(guess);
+        self.guesses += 1;
+        guess
+    }
+
+    // Takes the player's answer to the last guess.
+    pub fn answer(&mut self, answer: Answer) -> Result<Outcome, Contradiction> {
+        let guess = self.next_guess();
+        self.current = None;
+
+        match answer {
+            Answer::Correct => {
+                return Ok(Outcome::Found {
+                    number: guess,
+                    guesses: self.guesses,
+                })
+            }
+            Answer::Higher => {
+                self.above = Some(guess);
+                if guess >= self.to {
+                    return Err(self.contradiction());
+                }
+                self.from = guess + 1;
+            }
+            Answer::Lower => {
+                self.below = Some(guess);
+                if guess <= self.from {
+                    return Err(self.contradiction());
+                }
+                self.to = guess - 1;
+            }
+        }
+
+        Ok(Outcome::Continue)
+    }
+
+    fn contradiction(&self) -> Contradiction {
+        Contradiction {
+            above: self.above,
+            below: self.below,
+            low: self.low,
+            high: self.high,
+        }
+    }
+
+    pub fn guesses(&self) -> u32 {
+        self.guesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::optimal_guesses;
+    use std::cmp::Ordering;
+
+    // Answers every guess truthfully for the number the player has in mind.
+    fn play(reverse: &mut Reverse, number: u32) -> Result<Outcome, Contradiction> {
+        loop {
+            let answer = match number.cmp(&reverse.next_guess()) {
+                Ordering::Greater => Answer::Higher,
+                Ordering::Less => Answer::Lower,
+                Ordering::Equal => Answer::Correct,
+            };
+            match reverse.answer(answer)? {
+                Outcome::Continue => {}
+                found => return Ok(found),
+            }
+        }
+    }
+
+    // Gives the same answer until the computer runs out of numbers.
+    fn insist(reverse: &mut Reverse, answer: Answer) -> Contradiction {
+        loop {
+            if let Err(contradiction) = reverse.answer(answer) {
+                return contradiction;
+            }
+        }
+    }
+
+    #[test]
+    fn answers_are_read_in_short_and_long_form() {
+        assert_eq!(Answer::parse(" H\n"), Some(Answer::Higher));
+        assert_eq!(Answer::parse("lower"), Some(Answer::Lower));
+        assert_eq!(Answer::parse("c"), Some(Answer::Correct));
+        assert_eq!(Answer::parse("up"), None);
+    }
+
+    #[test]
+    fn honest_answers_find_every_number_within_the_optimal_guesses() {
+        for number in 1..=100 {
+            let mut reverse = Reverse::new(1, 100);
+            match play(&mut reverse, number) {
+                Ok(Outcome::Found {
+                    number: found,
+                    guesses,
+                }) => {
+                    assert_eq!(found, number);
+                    assert_eq!(guesses, reverse.guesses());
+                    assert!(guesses <= optimal_guesses(1, 100), "{}", number);
+                }
+                other => panic!("{}: {:?}", number, other),
+            }
+        }
+    }
+
+    #[test]
+    fn asking_twice_before_answering_repeats_the_guess() {
+        let mut reverse = Reverse::new(1, 100);
+        assert_eq!(reverse.next_guess(), 50);
+        assert_eq!(reverse.next_guess(), 50);
+        assert_eq!(reverse.guesses(), 1);
+    }
+
+    #[test]
+    fn higher_than_the_top_of_the_range_is_a_contradiction() {
+        let mut reverse = Reverse::new(1, 100);
+        assert_eq!(
+            insist(&mut reverse, Answer::Higher),
+            Contradiction {
+                above: Some(100),
+                below: None,
+                low: 1,
+                high: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn lower_than_the_bottom_of_the_range_is_a_contradiction() {
+        let mut reverse = Reverse::new(1, 100);
+        assert_eq!(
+            insist(&mut reverse, Answer::Lower),
+            Contradiction {
+                above: None,
+                below: Some(1),
+                low: 1,
+                high: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn answers_that_leave_no_number_between_them_are_a_contradiction() {
+        let mut reverse = Reverse::new(1, 100);
+        assert_eq!(reverse.next_guess(), 50);
+        assert_eq!(reverse.answer(Answer::Higher), Ok(Outcome::Continue));
+        assert_eq!(reverse.next_guess(), 75);
+        assert_eq!(reverse.answer(Answer::Lower), Ok(Outcome::Continue));
+        // Only 51 to 74 are left, and the player keeps saying lower.
+        assert_eq!(
+            insist(&mut reverse, Answer::Lower),
+            Contradiction {
+                above: Some(50),
+                below: Some(51),
+                low: 1,
+                high: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn a_range_of_one_number_is_guessed_at_once() {
+        let mut reverse = Reverse::new(7, 7);
+        assert_eq!(
+            play(&mut reverse, 7),
+            Ok(Outcome::Found {
+                number: 7,
+                guesses: 1,
+            })
+        );
+
+        let mut reverse = Reverse::new(7, 7);
+        assert_eq!(reverse.next_guess(), 7);
+        assert_eq!(
+            reverse.answer(Answer::Higher),
+            Err(Contradiction {
+                above: Some(7),
+                below: None,
+                low: 7,
+                high: 7,
+            })
+        );
+        let mut reverse = Reverse::new(7, 7);
+        assert_eq!(
+            reverse.answer(Answer::Lower),
+            Err(Contradiction {
+                above: None,
+                below: Some(7),
+                low: 7,
+                high: 7,
+            })
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::difficulty::Difficulty;
+use crate::json_file;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+pub const DEFAULT_SCORE_FILE: &str = "guessing_game_scores.json";
+
+// One finished round as it is stored in the score file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub player: String,
+    pub difficulty: Difficulty,
+    pub low: u32,
+    pub high: u32,
+    // Older score files didn't keep the guess limit, so their custom rounds
+    // are ranked as if they had none.
+    #[serde(default)]
+    pub max_guesses: Option<u32>,
+    pub guesses: u32,
+    pub elapsed_ms: u64,
+    // Seconds since the Unix epoch when the round ended.
+    pub timestamp: u64,
+    // Older score files only held wins, so a missing field means a win.
+    #[serde(default = "won_by_default")]
+    pub won: bool,
+}
+
+fn won_by_default() -> bool {
+    true
+}
+
+// Every recorded round, kept in a JSON file next to wherever the game is run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBoard {
+    pub scores: Vec<Score>,
+}
+
+impl ScoreBoard {
+    // Reads the score file. A file that doesn't exist yet is an empty board.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<ScoreBoard> {
+        json_file::load_or_default(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        json_file::save(self, path)
+    }
+
+    pub fn add(&mut self, score: Score) {
+        self.scores.push(score);
+    }
+
+    // The leaderboards for one difficulty, each holding the wins played under
+    // the same rules, best first: fewest guesses, then fastest, then whoever
+    // got there first.
+    // The presets always have the same range and limit, so each has a single
+    // board. Custom rounds, adaptive ones included, only compete with rounds
+    // of the same range and limit, since 1 to 2 is no match for 1 to 100000.
+    pub fn leaderboards(&self, difficulty: Difficulty) -> Vec<Leaderboard<'_>> {
+        let mut boards: Vec<Leaderboard> = Vec::new();
+        for score in self.scores.iter() {
+            if !score.won || score.difficulty != difficulty {
+                continue;
+            }
+
+            let rules = Rules::of(score);
+            match boards.iter_mut().find(|board| board.rules == rules) {
+                Some(board) => board.scores.push(score),
+                None => boards.push(Leaderboard {
+                    rules,
+                    scores: vec![score],
+                }),
+            }
+        }
+
+        for board in boards.iter_mut() {
+            board
+                .scores
+                .sort_by_key(|score| (score.guesses, score.elapsed_ms, score.timestamp));
+        }
+        boards.sort_by_key(|board| board.rules);
+        boards
+    }
+}
+
+// What makes two rounds comparable. Preset rounds only go by difficulty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rules {
+    Preset(Difficulty),
+    Custom {
+        low: u32,
+        high: u32,
+        max_guesses: Option<u32>,
+    },
+}
+
+impl Rules {
+    pub fn of(score: &Score) -> Rules {
+        match score.difficulty {
+            Difficulty::Custom => Rules::Custom {
+                low: score.low,
+                high: score.high,
+                max_guesses: score.max_guesses,
+            },
+            difficulty => Rules::Preset(difficulty),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard<'a> {
+    pub rules: Rules,
+    pub scores: Vec<&'a Score>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(difficulty: Difficulty, high: u32, max_guesses: Option<u32>, guesses: u32) -> Score {
+        Score {
+            player: String::from("tester"),
+            difficulty,
+            low: 1,
+            high,
+            max_guesses,
+            guesses,
+            elapsed_ms: 1000,
+            timestamp: 0,
+            won: true,
+        }
+    }
+
+    #[test]
+    fn custom_rounds_only_compete_with_the_same_rules() {
+        let board = ScoreBoard {
+            scores: vec![
+                score(Difficulty::Custom, 100_000, Some(20), 15),
+                score(Difficulty::Custom, 2, Some(2), 1),
+                score(Difficulty::Custom, 100_000, Some(20), 12),
+                score(Difficulty::Custom, 100_000, None, 3),
+            ],
+        };
+
+        let boards = board.leaderboards(Difficulty::Custom);
+        let summary: Vec<(Rules, Vec<u32>)> = boards
+            .iter()
+            .map(|board| {
+                let guesses = board.scores.iter().map(|score| score.guesses).collect();
+                (board.rules, guesses)
+            })
+            .collect();
+        let custom = |high, max_guesses| Rules::Custom {
+            low: 1,
+            high,
+            max_guesses,
+        };
+        assert_eq!(
+            summary,
+            [
+                (custom(2, Some(2)), vec![1]),
+                (custom(100_000, None), vec![3]),
+                (custom(100_000, Some(20)), vec![12, 15]),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_preset_has_one_board_of_wins() {
+        let mut lost = score(Difficulty::Normal, 100, Some(7), 7);
+        lost.won = false;
+        let board = ScoreBoard {
+            scores: vec![
+                score(Difficulty::Normal, 100, Some(7), 5),
+                // From before the limit was kept.
+                score(Difficulty::Normal, 100, None, 4),
+                score(Difficulty::Easy, 50, Some(10), 1),
+                lost,
+            ],
+        };
+
+        let boards = board.leaderboards(Difficulty::Normal);
+        assert_eq!(boards.len(), 1);
+        assert_eq!(boards[0].rules, Rules::Preset(Difficulty::Normal));
+        let guesses: Vec<u32> = boards[0].scores.iter().map(|score| score.guesses).collect();
+        assert_eq!(guesses, [4, 5]);
+    }
+
+    #[test]
+    fn older_score_files_still_load() {
+        let board: ScoreBoard = serde_json::from_str(
+            r#"{"scores": [{"player": "old", "difficulty": "custom", "low": 1,
+                "high": 10, "guesses": 3, "elapsed_ms": 500, "timestamp": 0}]}"#,
+        )
+        .unwrap();
+        assert!(board.scores[0].won);
+        assert_eq!(board.scores[0].max_guesses, None);
+    }
+}
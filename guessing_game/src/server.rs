@@ -0,0 +1,245 @@
+// Plays the guessing game over TCP with a line-based protocol.
+//
+// Every connection gets its own game. Lines are plain ASCII ending in '\n'.
+//
+// When a round starts the server sends
+//     HELLO <low> <high> <max guesses, or - for no limit>
+//
+// The client can then send
+//     GUESS <n>   answered with TOO_SMALL, TOO_BIG or WIN <guesses used>.
+//                 A guess that can't be taken is answered with
+//                 ERROR <not_a_number|negative|out_of_range|repeated|game_over>.
+//                 If the guess used up the last one, LOSE <secret> follows.
+//     NEW         starts a new round, answered with a fresh HELLO.
+//     QUIT        answered with BYE, then the server closes the connection.
+//
+// Anything else is answered with ERROR unknown_command.
+//
+// A session could look like this (C is the client, S the server):
+//     S: HELLO 1 100 7
+//     C: GUESS 50
+//     S: TOO_BIG
+//     C: GUESS 25
+//     S: WIN 2
+//     C: QUIT
+//     S: BYE
+
+use crate::difficulty::Settings;
+use crate::game::{Feedback, Game, Status};
+use rand::Rng;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+// Accepts connections forever, running each one's games on its own thread.
+// Connection n uses seed + n for its first round when a seed is given.
+pub fn serve(listener: TcpListener, settings: Settings, seed: Option<u64>) -> io::Result<()> {
+    for (connection, stream) in listener.incoming().enumerate() {
+        let stream = stream?;
+        let seed = match seed {
+            Some(seed) => seed.wrapping_add(connection as u64),
+            None => rand::thread_rng().gen(),
+        };
+
+        thread::spawn(move || {
+            // A client hanging up halfway through isn't the server's problem.
+            let _ = handle_connection(stream, settings, seed);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, settings: Settings, seed: u64) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    // Buffered so every reply goes out in one packet when it is flushed.
+    handle(reader, BufWriter::new(stream), settings, seed)
+}
+
+// Runs the protocol over any reader and writer, round n using seed + n.
+// Returns when the client quits or closes its end.
+pub fn handle<R, W>(reader: R, mut writer: W, settings: Settings, seed: u64) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut round = 0u64;
+    let mut game = Game::from_seed(settings, seed);
+    hello(&mut writer, &settings)?;
+
+    for line in reader.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+
+        match (words.next(), words.next(), words.next()) {
+            (Some("GUESS"), Some(guess), None) => {
+                let feedback = game.submit_guess(guess);
+                writeln!(writer, "{}", reply(feedback, &game))?;
+                if game.status() == Status::Lost {
+                    writeln!(writer, "LOSE {}", game.secret())?;
+                }
+            }
+            (Some("NEW"), None, None) => {
+                round += 1;
+                game = Game::from_seed(settings, seed.wrapping_add(round));
+                hello(&mut writer, &settings)?;
+            }
+            (Some("QUIT"), None, None) => {
+                writeln!(writer, "BYE")?;
+                break;
+            }
+            _ => writeln!(writer, "ERROR unknown_command")?,
+        }
+        writer.flush()?;
+    }
+
+    writer.flush()
+}
+
+fn hello<W: Write>(writer: &mut W, settings: &Settings) -> io::Result<()> {
+    let max_guesses = match settings.max_guesses {
+        Some(max) => max.to_string(),
+        None => String::from("-"),
+    };
+    writeln!(
+        writer,
+        "HELLO {} {} {}",
+        settings.low, settings.high, max_guesses
+    )?;
+    writer.flush()
+}
+
+fn reply(feedback: Feedback, game: &Game) -> String {
+    match feedback {
+        Feedback::TooSmall => String::from("TOO_SMALL"),
+        Feedback::TooBig => String::from("TOO_BIG"),
+        Feedback::Win => format!("WIN {}", game.guesses()),
+        Feedback::Invalid(reason) => format!("ERROR {}", reason.code()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+    use std::io::Read;
+
+    const SEED: u64 = 7;
+
+    // A server on a free port of localhost, running in the background.
+    fn start() -> (u16, Settings) {
+        let settings = Settings::preset(Difficulty::Normal);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || serve(listener, settings, Some(SEED)));
+        (port, settings)
+    }
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(port: u16) -> Client {
+            let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            Client {
+                reader: BufReader::new(stream.try_clone().unwrap()),
+                writer: stream,
+            }
+        }
+
+        fn read(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            line.trim_end().to_string()
+        }
+
+        fn send(&mut self, line: &str) -> String {
+            self.writer
+                .write_all(format!("{}\n", line).as_bytes())
+                .unwrap();
+            self.read()
+        }
+    }
+
+    #[test]
+    fn a_client_can_play_a_round_to_the_win() {
+        let (port, settings) = start();
+        let mut client = Client::connect(port);
+        assert_eq!(client.read(), "HELLO 1 100 7");
+
+        let secret = Game::from_seed(settings, SEED).secret();
+        let wrong = if secret == 1 { 2 } else { 1 };
+        let expected = if wrong < secret {
+            "TOO_SMALL"
+        } else {
+            "TOO_BIG"
+        };
+        assert_eq!(client.send(&format!("GUESS {}", wrong)), expected);
+        assert_eq!(client.send(&format!("GUESS {}", wrong)), "ERROR repeated");
+        assert_eq!(client.send("GUESS ten"), "ERROR not_a_number");
+        assert_eq!(client.send("GUESS -3"), "ERROR negative");
+        assert_eq!(client.send("GUESS 101"), "ERROR out_of_range");
+        assert_eq!(client.send(&format!("GUESS {}", secret)), "WIN 2");
+        assert_eq!(client.send("GUESS 5"), "ERROR game_over");
+        assert_eq!(client.send("QUIT"), "BYE");
+
+        // The server hangs up after BYE.
+        let mut rest = String::new();
+        client.reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn running_out_of_guesses_tells_the_secret() {
+        let (port, settings) = start();
+        let mut client = Client::connect(port);
+        client.read();
+
+        let secret = Game::from_seed(settings, SEED).secret();
+        let misses: Vec<u32> = (1..=100).filter(|&n| n != secret).take(7).collect();
+        for &guess in &misses[..6] {
+            client.send(&format!("GUESS {}", guess));
+        }
+        client.send(&format!("GUESS {}", misses[6]));
+        assert_eq!(client.read(), format!("LOSE {}", secret));
+    }
+
+    #[test]
+    fn new_starts_the_next_round() {
+        let (port, settings) = start();
+        let mut client = Client::connect(port);
+        client.read();
+        assert_eq!(client.send("GUESS 1 2"), "ERROR unknown_command");
+        assert_eq!(client.send("HELLO"), "ERROR unknown_command");
+        assert_eq!(client.send("NEW"), "HELLO 1 100 7");
+
+        let secret = Game::from_seed(settings, SEED + 1).secret();
+        assert_eq!(client.send(&format!("GUESS {}", secret)), "WIN 1");
+    }
+
+    #[test]
+    fn every_connection_has_its_own_game() {
+        let (port, settings) = start();
+        let mut first = Client::connect(port);
+        first.read();
+        let mut second = Client::connect(port);
+        second.read();
+
+        let secret = Game::from_seed(settings, SEED + 1).secret();
+        assert_eq!(second.send(&format!("GUESS {}", secret)), "WIN 1");
+        // The first connection's round hasn't seen that guess.
+        assert_ne!(first.send("GUESS 50"), "ERROR game_over");
+    }
+
+    #[test]
+    fn handle_works_on_any_reader_and_writer() {
+        let settings = Settings::custom(5, 5, None).unwrap();
+        let mut output = Vec::new();
+        handle(&b"GUESS 5\nQUIT\nGUESS 5\n"[..], &mut output, settings, 0).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "HELLO 5 5 -\nWIN 1\nBYE\n"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::difficulty::Settings;
+use crate::game::{Feedback, Game};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// The names accepted by strategy(), in the order the benchmark reports them.
+pub const STRATEGIES: [&str; 3] = ["binary", "random", "linear"];
+
+// A bot that plays the guessing game.
+// Before every round the strategy is reset with the range the secret is in,
+// then it is asked for a guess and told the game's answer until the round ends.
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn reset(&mut self, low: u32, high: u32);
+    fn next_guess(&mut self) -> u32;
+    fn observe(&mut self, guess: u32, feedback: Feedback);
+}
+
+// Looks a strategy up by name. The seed is only used by strategies that
+// need randomness, so a benchmark run can be repeated.
+pub fn strategy(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "binary" => Some(Box::new(Binary::default())),
+        "random" => Some(Box::new(Random::new(seed))),
+        "linear" => Some(Box::new(Linear::default())),
+        _ => None,
+    }
+}
+
+// The smallest range that can still hold the secret, narrowed by every answer.
+#[derive(Debug, Clone, Copy, Default)]
+struct Bounds {
+    low: u32,
+    high: u32,
+}
+
+impl Bounds {
+    fn narrow(&mut self, guess: u32, feedback: Feedback) {
+        match feedback {
+            Feedback::TooSmall => self.low = guess + 1,
+            Feedback::TooBig => self.high = guess - 1,
+            Feedback::Win | Feedback::Invalid(_) => {}
+        }
+    }
+}
+
+// Always guesses the middle of what's left, halving the range each time.
+// Never needs more than floor(log2(range size)) + 1 guesses.
+#[derive(Debug, Default)]
+pub struct Binary {
+    bounds: Bounds,
+}
+
+impl Strategy for Binary {
+    fn name(&self) -> &'static str {
+        "binary"
+    }
+
+    fn reset(&mut self, low: u32, high: u32) {
+        self.bounds = Bounds { low, high };
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        let Bounds { low, high } = self.bounds;
+        low + (high - low) / 2
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+// Guesses any number that's still possible. It uses the answers, but on
+// average it needs around 2 ln(n) guesses where Binary needs log2(n).
+#[derive(Debug)]
+pub struct Random {
+    bounds: Bounds,
+    rng: StdRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random {
+            bounds: Bounds::default(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn reset(&mut self, low: u32, high: u32) {
+        self.bounds = Bounds { low, high };
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        let Bounds { low, high } = self.bounds;
+        self.rng.gen_range(low, high + 1)
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+// Counts up from the lowest number, ignoring the answers.
+// Needs as many guesses as the secret is far from the bottom of the range.
+#[derive(Debug, Default)]
+pub struct Linear {
+    next: u32,
+}
+
+impl Strategy for Linear {
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+
+    fn reset(&mut self, low: u32, _high: u32) {
+        self.next = low;
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.next
+    }
+
+    fn observe(&mut self, guess: u32, _feedback: Feedback) {
+        self.next = guess + 1;
+    }
+}
+
+// Lets the strategy play the game until it is over, returning every guess
+// it made together with the game's answer.
+pub fn play(game: &mut Game, strategy: &mut dyn Strategy) -> Vec<(u32, Feedback)> {
+    let settings = game.settings();
+    strategy.reset(settings.low, settings.high);
+
+    let mut turns = Vec::new();
+    while !game.is_over() {
+        let guess = strategy.next_guess();
+        let feedback = game.guess(guess);
+        strategy.observe(guess, feedback);
+        turns.push((guess, feedback));
+
+        // A strategy that keeps making invalid guesses would never finish.
+        if let Feedback::Invalid(_) = feedback {
+            break;
+        }
+    }
+    turns
+}
+
+// How a strategy did over a number of rounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Benchmark {
+    pub strategy: &'static str,
+    pub games: u32,
+    pub mean_guesses: f64,
+    pub max_guesses: u32,
+}
+
+// Plays `games` rounds with the strategy, the n-th round using seed + n for
+// its secret. The guess limit is lifted so every round is played to a win.
+pub fn benchmark(
+    strategy: &mut dyn Strategy,
+    settings: Settings,
+    games: u32,
+    seed: u64,
+) -> Benchmark {
+    let settings = Settings {
+        max_guesses: None,
+        ..settings
+    };
+
+    let mut total = 0u64;
+    let mut max_guesses = 0;
+    for n in 0..games {
+        let mut game = Game::from_seed(settings, seed.wrapping_add(u64::from(n)));
+        play(&mut game, strategy);
+
+        total += u64::from(game.guesses());
+        max_guesses = max_guesses.max(game.guesses());
+    }
+
+    Benchmark {
+        strategy: strategy.name(),
+        games,
+        mean_guesses: if games == 0 {
+            0.0
+        } else {
+            total as f64 / f64::from(games)
+        },
+        max_guesses,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+    use crate::game::Status;
+    use crate::stats::optimal_guesses;
+
+    #[test]
+    fn strategies_are_found_by_name() {
+        for &name in STRATEGIES.iter() {
+            assert_eq!(strategy(name, 0).unwrap().name(), name);
+        }
+        assert!(strategy("psychic", 0).is_none());
+    }
+
+    #[test]
+    fn binary_never_needs_more_than_the_optimal_guesses() {
+        for &difficulty in &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            let settings = Settings::preset(difficulty);
+            let optimal = optimal_guesses(settings.low, settings.high);
+            for seed in 0..500 {
+                let mut game = Game::from_seed(settings, seed);
+                play(&mut game, &mut Binary::default());
+                assert_eq!(game.status(), Status::Won, "seed {}", seed);
+                assert!(game.guesses() <= optimal, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn linear_counts_up_to_the_secret() {
+        let settings = Settings::custom(20, 60, None).unwrap();
+        for secret in 20..=60 {
+            let mut game = Game::new(settings, secret);
+            let turns = play(&mut game, &mut Linear::default());
+            assert_eq!(game.status(), Status::Won);
+            assert_eq!(game.guesses(), secret - 20 + 1);
+            assert_eq!(turns.first().map(|&(guess, _)| guess), Some(20));
+            assert_eq!(turns.last(), Some(&(secret, Feedback::Win)));
+        }
+    }
+
+    #[test]
+    fn random_only_guesses_what_is_still_possible() {
+        let settings = Settings::preset(Difficulty::Hard);
+        for seed in 0..50 {
+            let mut game = Game::from_seed(settings, seed);
+            let turns = play(&mut game, &mut Random::new(seed));
+            assert_eq!(game.status(), Status::Won, "seed {}", seed);
+            assert!(turns
+                .iter()
+                .all(|(_, feedback)| !matches!(feedback, Feedback::Invalid(_))));
+        }
+    }
+
+    #[test]
+    fn a_benchmark_of_no_games_is_all_zeros() {
+        let settings = Settings::preset(Difficulty::Normal);
+        assert_eq!(
+            benchmark(&mut Binary::default(), settings, 0, 1),
+            Benchmark {
+                strategy: "binary",
+                games: 0,
+                mean_guesses: 0.0,
+                max_guesses: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_benchmark() {
+        let settings = Settings::preset(Difficulty::Hard);
+        for &name in STRATEGIES.iter() {
+            let first = benchmark(&mut *strategy(name, 5).unwrap(), settings, 100, 9);
+            let again = benchmark(&mut *strategy(name, 5).unwrap(), settings, 100, 9);
+            assert_eq!(first, again, "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_benchmark_plays_past_the_guess_limit() {
+        // Linear needs up to 100 guesses where normal allows 7.
+        let settings = Settings::preset(Difficulty::Normal);
+        let result = benchmark(&mut Linear::default(), settings, 200, 0);
+        assert!(result.max_guesses > 7);
+        assert!(result.mean_guesses <= f64::from(result.max_guesses));
+    }
+}
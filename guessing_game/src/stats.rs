@@ -0,0 +1,304 @@
+use crate::difficulty::Difficulty;
+use crate::scores::Score;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// The most guesses a binary search needs for a range: floor(log2(size)) + 1.
+// Nobody can be sure of winning with fewer. A range that is upside down, which
+// only a hand-edited score file can have, counts as a single number.
+pub fn optimal_guesses(low: u32, high: u32) -> u32 {
+    binary_search_guesses(u64::from(high.saturating_sub(low)) + 1)
+}
+
+// The same for any number of values in order.
+pub fn binary_search_guesses(size: u64) -> u32 {
+    64 - size.leading_zeros()
+}
+
+// Figures over a set of recorded rounds. Guess counts only look at wins.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub games: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    pub mean_guesses: Option<f64>,
+    pub median_guesses: Option<f64>,
+    // How many wins took each number of guesses.
+    pub histogram: BTreeMap<u32, u32>,
+    pub players: Vec<PlayerStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerStats {
+    pub player: String,
+    pub games: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    pub mean_guesses: Option<f64>,
+    pub median_guesses: Option<f64>,
+    // The mean of optimal_guesses() over the ranges of the player's wins.
+    pub mean_optimal: Option<f64>,
+    // How many guesses the player needed on average beyond the optimal bound.
+    // Below zero means they were luckier than a binary search could promise.
+    pub mean_over_optimal: Option<f64>,
+}
+
+impl Stats {
+    // Aggregates the rounds, optionally only those of one difficulty or player.
+    // Players are listed by name.
+    pub fn new(scores: &[Score], difficulty: Option<Difficulty>, player: Option<&str>) -> Stats {
+        let scores: Vec<&Score> = scores
+            .iter()
+            .filter(|score| difficulty.is_none_or(|d| score.difficulty == d))
+            .filter(|score| player.is_none_or(|p| score.player == p))
+            .collect();
+
+        let mut by_player: BTreeMap<&str, Vec<&Score>> = BTreeMap::new();
+        for &score in &scores {
+            by_player
+                .entry(score.player.as_str())
+                .or_default()
+                .push(score);
+        }
+
+        let mut histogram = BTreeMap::new();
+        for score in scores.iter().filter(|score| score.won) {
+            *histogram.entry(score.guesses).or_insert(0) += 1;
+        }
+
+        let totals = Totals::new(&scores);
+        Stats {
+            games: totals.games,
+            wins: totals.wins,
+            win_rate: totals.win_rate(),
+            mean_guesses: mean(&totals.guesses),
+            median_guesses: median(&totals.guesses),
+            histogram,
+            players: by_player
+                .into_iter()
+                .map(|(player, scores)| PlayerStats::new(player, &scores))
+                .collect(),
+        }
+    }
+}
+
+impl PlayerStats {
+    fn new(player: &str, scores: &[&Score]) -> PlayerStats {
+        let totals = Totals::new(scores);
+        let wins: Vec<&Score> = scores.iter().copied().filter(|score| score.won).collect();
+        let optimal: Vec<u32> = wins
+            .iter()
+            .map(|score| optimal_guesses(score.low, score.high))
+            .collect();
+        let over: Vec<f64> = wins
+            .iter()
+            .zip(&optimal)
+            .map(|(score, &optimal)| f64::from(score.guesses) - f64::from(optimal))
+            .collect();
+
+        PlayerStats {
+            player: String::from(player),
+            games: totals.games,
+            wins: totals.wins,
+            win_rate: totals.win_rate(),
+            mean_guesses: mean(&totals.guesses),
+            median_guesses: median(&totals.guesses),
+            mean_optimal: mean(&optimal),
+            mean_over_optimal: if over.is_empty() {
+                None
+            } else {
+                Some(over.iter().sum::<f64>() / over.len() as f64)
+            },
+        }
+    }
+}
+
+struct Totals {
+    games: u32,
+    wins: u32,
+    // The guess counts of the wins.
+    guesses: Vec<u32>,
+}
+
+impl Totals {
+    fn new(scores: &[&Score]) -> Totals {
+        let guesses: Vec<u32> = scores
+            .iter()
+            .filter(|score| score.won)
+            .map(|score| score.guesses)
+            .collect();
+        Totals {
+            games: scores.len() as u32,
+            wins: guesses.len() as u32,
+            guesses,
+        }
+    }
+
+    fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            f64::from(self.wins) / f64::from(self.games)
+        }
+    }
+}
+
+fn mean(values: &[u32]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let total: u64 = values.iter().map(|&value| u64::from(value)).sum();
+    Some(total as f64 / values.len() as f64)
+}
+
+fn median(values: &[u32]) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(f64::from(values[middle])),
+        _ => Some((f64::from(values[middle - 1]) + f64::from(values[middle])) / 2.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn score(player: &str, difficulty: Difficulty, high: u32, guesses: u32, won: bool) -> Score {
+        Score {
+            player: String::from(player),
+            difficulty,
+            low: 1,
+            high,
+            max_guesses: None,
+            guesses,
+            elapsed_ms: 1000,
+            timestamp: 0,
+            won,
+        }
+    }
+
+    fn scores() -> Vec<Score> {
+        vec![
+            score("ada", Difficulty::Normal, 100, 5, true),
+            score("ada", Difficulty::Normal, 100, 7, true),
+            score("ada", Difficulty::Easy, 50, 10, false),
+            score("bob", Difficulty::Normal, 100, 4, true),
+            score("bob", Difficulty::Hard, 1000, 12, true),
+            score("bob", Difficulty::Easy, 50, 3, true),
+        ]
+    }
+
+    #[test]
+    fn optimal_guesses_is_a_binary_search() {
+        assert_eq!(optimal_guesses(1, 1), 1);
+        assert_eq!(optimal_guesses(1, 2), 2);
+        assert_eq!(optimal_guesses(1, 100), 7);
+        assert_eq!(optimal_guesses(1, 1000), 10);
+        assert_eq!(optimal_guesses(0, u32::MAX), 33);
+    }
+
+    #[test]
+    fn an_upside_down_range_does_not_panic() {
+        assert_eq!(optimal_guesses(100, 1), 1);
+    }
+
+    #[test]
+    fn every_round_is_summed_up() {
+        let stats = Stats::new(&scores(), None, None);
+        assert_eq!((stats.games, stats.wins), (6, 5));
+        assert_eq!(stats.win_rate, 5.0 / 6.0);
+        assert_eq!(stats.mean_guesses, Some(31.0 / 5.0));
+        assert_eq!(stats.median_guesses, Some(5.0));
+        // The loss took 10 guesses but isn't in there.
+        let histogram: Vec<(u32, u32)> = stats.histogram.into_iter().collect();
+        assert_eq!(histogram, [(3, 1), (4, 1), (5, 1), (7, 1), (12, 1)]);
+    }
+
+    #[test]
+    fn players_are_compared_to_a_binary_search() {
+        let stats = Stats::new(&scores(), None, None);
+        let names: Vec<&str> = stats.players.iter().map(|p| p.player.as_str()).collect();
+        assert_eq!(names, ["ada", "bob"]);
+
+        let ada = &stats.players[0];
+        assert_eq!((ada.games, ada.wins), (3, 2));
+        assert_eq!(ada.win_rate, 2.0 / 3.0);
+        assert_eq!(ada.mean_guesses, Some(6.0));
+        // Two wins, so halfway between 5 and 7.
+        assert_eq!(ada.median_guesses, Some(6.0));
+        assert_eq!(ada.mean_optimal, Some(7.0));
+        assert_eq!(ada.mean_over_optimal, Some(-1.0));
+
+        let bob = &stats.players[1];
+        assert_eq!(bob.win_rate, 1.0);
+        assert_eq!(bob.median_guesses, Some(4.0));
+        // 7, 10 and 6 guesses for 1-100, 1-1000 and 1-50.
+        assert_eq!(bob.mean_optimal, Some(23.0 / 3.0));
+        assert_eq!(bob.mean_over_optimal, Some(-4.0 / 3.0));
+    }
+
+    #[test]
+    fn the_median_of_an_even_number_of_wins_is_halfway() {
+        let mut scores = scores();
+        scores.push(score("cy", Difficulty::Normal, 100, 8, true));
+        let stats = Stats::new(&scores, None, None);
+        // 3, 4, 5, 7, 8, 12.
+        assert_eq!(stats.median_guesses, Some(6.0));
+    }
+
+    #[test]
+    fn rounds_can_be_picked_by_difficulty_and_player() {
+        let normal = Stats::new(&scores(), Some(Difficulty::Normal), None);
+        assert_eq!((normal.games, normal.wins), (3, 3));
+        assert_eq!(normal.median_guesses, Some(5.0));
+        assert_eq!(normal.players.len(), 2);
+
+        let bob = Stats::new(&scores(), None, Some("bob"));
+        assert_eq!((bob.games, bob.wins), (3, 3));
+        assert_eq!(bob.players.len(), 1);
+        assert_eq!(bob.players[0].player, "bob");
+
+        let lost = Stats::new(&scores(), Some(Difficulty::Easy), Some("ada"));
+        assert_eq!((lost.games, lost.wins, lost.win_rate), (1, 0, 0.0));
+        assert_eq!(lost.mean_guesses, None);
+        assert_eq!(lost.median_guesses, None);
+        assert!(lost.histogram.is_empty());
+        assert_eq!(lost.players[0].mean_optimal, None);
+        assert_eq!(lost.players[0].mean_over_optimal, None);
+
+        let nobody = Stats::new(&scores(), None, Some("dee"));
+        assert_eq!((nobody.games, nobody.win_rate), (0, 0.0));
+        assert!(nobody.players.is_empty());
+    }
+
+    #[test]
+    fn stats_turn_into_json() {
+        let stats = Stats::new(&scores(), Some(Difficulty::Hard), None);
+        assert_eq!(
+            serde_json::to_value(&stats).unwrap(),
+            json!({
+                "games": 1,
+                "wins": 1,
+                "win_rate": 1.0,
+                "mean_guesses": 12.0,
+                "median_guesses": 12.0,
+                "histogram": {"12": 1},
+                "players": [{
+                    "player": "bob",
+                    "games": 1,
+                    "wins": 1,
+                    "win_rate": 1.0,
+                    "mean_guesses": 12.0,
+                    "median_guesses": 12.0,
+                    "mean_optimal": 10.0,
+                    "mean_over_optimal": 2.0,
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use crate::console;
+use crate::difficulty::Settings;
+use crate::game::Game;
+use crate::json_file;
+use crate::messages::Lang;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    // Anything the game printed while waiting for a guess.
+    Prompt,
+    // A line of input, exactly as typed.
+    Guess,
+    // A line the game printed in answer to the guess before it.
+    Response,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    // Milliseconds since the round started.
+    pub at_ms: u64,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+// Everything needed to play a round again: its settings, its seed and what
+// was said on both sides.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    pub settings: Settings,
+    pub seed: u64,
+    // The language the round was played in. Older transcripts are English.
+    #[serde(default)]
+    pub lang: Lang,
+    // Seconds since the Unix epoch when the round started.
+    pub started_at: u64,
+    pub events: Vec<Event>,
+}
+
+impl Transcript {
+    // Reads a transcript, turning down one whose settings no round could be
+    // played with, such as a hand-edited range that is upside down.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Transcript> {
+        let transcript: Transcript = json_file::load(path)?;
+        transcript
+            .settings
+            .check()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(transcript)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        json_file::save(self, path)
+    }
+
+    // Plays every recorded guess against a fresh game with the same settings
+    // and seed, checking the game answers each one exactly as recorded.
+    // Returns how many guesses were replayed.
+    pub fn replay(&self) -> Result<usize, Mismatch> {
+        let mut game = Game::from_seed(self.settings, self.seed);
+        let mut guesses = 0;
+        let mut events = self.events.iter().peekable();
+
+        while let Some(event) = events.next() {
+            if event.kind != EventKind::Guess {
+                continue;
+            }
+            guesses += 1;
+
+            let feedback = game.submit_guess(&event.text);
+            let actual = console::responses(self.lang, &game, &event.text, feedback);
+
+            let mut expected = Vec::new();
+            while let Some(response) = events.peek() {
+                if response.kind != EventKind::Response {
+                    break;
+                }
+                expected.push(response.text.clone());
+                events.next();
+            }
+
+            if actual != expected {
+                return Err(Mismatch {
+                    guess: guesses,
+                    input: event.text.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(guesses)
+    }
+}
+
+// A guess the game no longer answers the way the transcript says it did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    // Which guess it was, counting from 1.
+    pub guess: usize,
+    pub input: String,
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "guess {} ({:?}) was answered differently",
+            self.guess, self.input
+        )?;
+        writeln!(f, "recorded:")?;
+        for line in &self.expected {
+            writeln!(f, "  {}", line)?;
+        }
+        writeln!(f, "replayed:")?;
+        for line in &self.actual {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// Builds a transcript while a round is being played.
+#[derive(Debug)]
+pub struct Recorder {
+    transcript: Transcript,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn new(settings: Settings, seed: u64, lang: Lang) -> Recorder {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+
+        Recorder {
+            transcript: Transcript {
+                settings,
+                seed,
+                lang,
+                started_at,
+                events: Vec::new(),
+            },
+            started: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self, kind: EventKind, text: &str) {
+        self.transcript.events.push(Event {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            kind,
+            text: String::from(text),
+        });
+    }
+
+    pub fn finish(self) -> Transcript {
+        self.transcript
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+    use std::env;
+    use std::fs;
+
+    // Rounds recorded with --record. If the game ever answers differently,
+    // these stop replaying.
+    const GOLDEN: [(&str, &str); 2] = [
+        (
+            "hints_lost_en",
+            include_str!("../tests/golden/hints_lost_en.json"),
+        ),
+        (
+            "count_invalid_won_de",
+            include_str!("../tests/golden/count_invalid_won_de.json"),
+        ),
+    ];
+
+    fn golden(name: &str) -> Transcript {
+        let (_, json) = GOLDEN
+            .iter()
+            .find(|(golden, _)| *golden == name)
+            .expect("a golden transcript");
+        serde_json::from_str(json).unwrap()
+    }
+
+    // Writes the JSON to a file of the test's own and loads it back.
+    fn load_json(test: &str, json: &str) -> io::Result<Transcript> {
+        let path = env::temp_dir().join(format!(
+            "guessing_game_transcript_{}_{}.json",
+            test,
+            std::process::id()
+        ));
+        fs::write(&path, json).unwrap();
+        let transcript = Transcript::load(&path);
+        let _ = fs::remove_file(&path);
+        transcript
+    }
+
+    #[test]
+    fn golden_transcripts_replay() {
+        // Every line of input counts, the invalid ones too.
+        assert_eq!(golden("hints_lost_en").replay(), Ok(9));
+        assert_eq!(golden("count_invalid_won_de").replay(), Ok(4));
+    }
+
+    #[test]
+    fn golden_transcripts_load_and_check_out() {
+        for (name, json) in GOLDEN.iter() {
+            let transcript = load_json(name, json).unwrap();
+            assert_eq!(transcript, golden(name));
+        }
+    }
+
+    #[test]
+    fn a_changed_answer_is_reported() {
+        let mut transcript = golden("hints_lost_en");
+        let response = transcript
+            .events
+            .iter_mut()
+            .find(|event| event.text == "Warm. Closer than last time.")
+            .unwrap();
+        response.text = String::from("Cold. Closer than last time.");
+
+        let mismatch = transcript.replay().unwrap_err();
+        assert_eq!(mismatch.guess, 4);
+        assert_eq!(mismatch.input, "30");
+        assert_eq!(
+            mismatch.expected,
+            [
+                "You guessed: 30",
+                "Too big!",
+                "Cold. Closer than last time.",
+                "Guesses left: 5"
+            ]
+        );
+        assert_eq!(mismatch.actual[2], "Warm. Closer than last time.");
+    }
+
+    #[test]
+    fn a_different_seed_is_reported() {
+        let mut transcript = golden("count_invalid_won_de");
+        transcript.seed += 1;
+        assert!(transcript.replay().is_err());
+    }
+
+    #[test]
+    fn a_recorded_round_replays() {
+        let settings = Settings::preset(Difficulty::Easy);
+        let mut game = Game::from_seed(settings, 3);
+        let mut recorder = Recorder::new(settings, 3, Lang::English);
+        for input in ["25", "x", "12", "37", "6", "18"].iter() {
+            recorder.record(EventKind::Guess, input);
+            let feedback = game.submit_guess(input);
+            for line in console::responses(Lang::English, &game, input, feedback) {
+                recorder.record(EventKind::Response, &line);
+            }
+        }
+
+        assert_eq!(recorder.finish().replay(), Ok(6));
+    }
+
+    #[test]
+    fn transcripts_without_a_language_are_english() {
+        let mut json: serde_json::Value = serde_json::from_str(GOLDEN[0].1).unwrap();
+        json.as_object_mut().unwrap().remove("lang");
+        let transcript: Transcript = serde_json::from_value(json).unwrap();
+        assert_eq!(transcript.lang, Lang::English);
+    }
+
+    #[test]
+    fn settings_no_round_could_use_are_turned_down() {
+        let cases = [
+            ("upside_down", "low", 500),
+            ("too_high", "high", u64::from(u32::MAX)),
+            ("no_guesses", "max_guesses", 0),
+        ];
+        for (test, field, value) in cases.iter() {
+            let mut json: serde_json::Value = serde_json::from_str(GOLDEN[0].1).unwrap();
+            json["settings"][field] = serde_json::Value::from(*value);
+
+            let error = load_json(test, &json.to_string()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{}", test);
+        }
+    }
+
+    #[test]
+    fn broken_json_is_invalid_data() {
+        let error = load_json("broken", "{\"settings\":").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,182 @@
+// A full-screen front end for a single round.
+// The terminal is put in raw mode on an alternate screen, showing the range
+// the secret can still be in, every guess so far, the guesses left and a timer.
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use guessing_game::{Feedback, Game, Lang, Message, Status};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: u32 = 50;
+const HISTORY_LINES: usize = 10;
+
+// Puts the terminal back the way it was, even if drawing fails halfway.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Screen {
+    lang: Lang,
+    input: String,
+    message: String,
+    history: Vec<String>,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+// Plays the game until it is over or the player presses Esc.
+// Returns how long the round took.
+pub fn play(game: &mut Game, lang: Lang) -> io::Result<Duration> {
+    let _terminal = RawTerminal::enter()?;
+    let mut screen = Screen {
+        lang,
+        input: String::new(),
+        message: lang.text(Message::TuiHelp),
+        history: Vec::new(),
+        started: Instant::now(),
+        finished: None,
+    };
+
+    loop {
+        draw(game, &screen)?;
+
+        // Wake up a few times a second so the timer keeps ticking.
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+
+        if is_quit(&key) {
+            break;
+        }
+        if game.is_over() {
+            // Any key leaves the final screen.
+            break;
+        }
+
+        match key.code {
+            KeyCode::Char(c) if !c.is_control() && screen.input.len() < 12 => screen.input.push(c),
+            KeyCode::Backspace => {
+                screen.input.pop();
+            }
+            KeyCode::Enter if !screen.input.is_empty() => {
+                let input = std::mem::take(&mut screen.input);
+                let feedback = game.submit_guess(&input);
+                answer(game, &mut screen, &input, feedback);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(screen.finished.unwrap_or_else(|| screen.started.elapsed()))
+}
+
+fn is_quit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Esc
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+fn answer(game: &Game, screen: &mut Screen, input: &str, feedback: Feedback) {
+    let lang = screen.lang;
+    if let Feedback::Invalid(_) = feedback {
+        screen.message = lang.feedback(&feedback);
+    } else {
+        let mut line = format!("{:>10}  {}", input.trim(), lang.feedback(&feedback));
+        if let Some(hint) = game.hint() {
+            line.push_str(&format!("  {}", lang.hint(&hint)));
+        }
+        screen.history.push(line);
+        screen.message.clear();
+    }
+
+    match game.status() {
+        Status::Won => screen.message = lang.text(Message::TuiWin),
+        Status::Lost => screen.message = lang.text(Message::TuiLose(game.secret())),
+        Status::Playing => return,
+    }
+    screen.finished = Some(screen.started.elapsed());
+}
+
+fn draw(game: &Game, screen: &Screen) -> io::Result<()> {
+    let settings = game.settings();
+    let (low, high) = game.bounds();
+    let elapsed = screen.finished.unwrap_or_else(|| screen.started.elapsed());
+    let lang = screen.lang;
+    let left = match game.guesses_left() {
+        Some(left) => lang.text(Message::TuiGuessesOf {
+            left,
+            max: settings.max_guesses.unwrap_or(0),
+        }),
+        None => lang.text(Message::TuiUnlimited),
+    };
+
+    let mut lines = vec![
+        lang.text(Message::TuiTitle {
+            difficulty: settings.difficulty,
+            low: settings.low,
+            high: settings.high,
+        }),
+        String::new(),
+        if low == high {
+            lang.text(Message::TuiExactly(low))
+        } else {
+            lang.text(Message::TuiBetween { low, high })
+        },
+        range_bar(settings.low, settings.high, low, high),
+        String::new(),
+        lang.text(Message::TuiStatus {
+            left: &left,
+            minutes: elapsed.as_secs() / 60,
+            seconds: elapsed.as_secs() % 60,
+        }),
+        String::new(),
+        lang.text(Message::TuiGuesses),
+    ];
+
+    let skipped = screen.history.len().saturating_sub(HISTORY_LINES);
+    lines.extend(screen.history.iter().skip(skipped).cloned());
+    lines.push(String::new());
+    lines.push(screen.message.clone());
+    if !game.is_over() {
+        lines.push(format!("> {}_", screen.input));
+    }
+
+    let mut stdout = io::stdout();
+    queue!(stdout, Clear(ClearType::All))?;
+    for (row, line) in lines.iter().enumerate() {
+        queue!(stdout, MoveTo(1, row as u16), Print(line))?;
+    }
+    stdout.flush()
+}
+
+// A bar for the whole range with the part the secret can still be in filled in.
+fn range_bar(first: u32, last: u32, low: u32, high: u32) -> String {
+    let size = u64::from(last - first) + 1;
+    let cell = |n: u32| u64::from(n - first) * u64::from(BAR_WIDTH) / size;
+    let (from, to) = (cell(low), cell(high));
+
+    let bar: String = (0..u64::from(BAR_WIDTH))
+        .map(|i| if i >= from && i <= to { '#' } else { '.' })
+        .collect();
+    format!("[{}]", bar)
+}
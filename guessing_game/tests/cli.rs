@@ -0,0 +1,144 @@
+// Runs the guessing_game binary with a fixed seed, feeds it guesses on stdin
+// and checks what it prints, line for line.
+
+use guessing_game::{Difficulty, Game, Settings};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const SEED: u64 = 42;
+
+// A score file of the test's own, so runs never touch the real one.
+fn score_file(test: &str) -> PathBuf {
+    env::temp_dir().join(format!(
+        "guessing_game_{}_{}.json",
+        test,
+        std::process::id()
+    ))
+}
+
+// Runs the binary with the arguments and input, returning its stdout and
+// whether it exited successfully.
+fn run(test: &str, args: &[&str], input: &str) -> (String, bool) {
+    let scores = score_file(test);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(args)
+        .arg("--score-file")
+        .arg(&scores)
+        .args(["--name", "tester", "--lang", "en"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("the binary runs");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("the input is written");
+    let output = child.wait_with_output().expect("the binary finishes");
+    let _ = fs::remove_file(&scores);
+
+    (
+        String::from_utf8(output.stdout).expect("the output is UTF-8"),
+        output.status.success(),
+    )
+}
+
+fn secret() -> u32 {
+    Game::from_seed(Settings::preset(Difficulty::Normal), SEED).secret()
+}
+
+#[test]
+fn a_seeded_round_prints_the_same_transcript_every_time() {
+    let seed = SEED.to_string();
+    let input = "1\n2\n3\n";
+    let first = run("same_twice_a", &["--seed", &seed], input);
+    let second = run("same_twice_b", &["--seed", &seed], input);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn a_lost_round_matches_the_expected_transcript() {
+    let secret = secret();
+    // Seven guesses that all miss, one of them a typo that doesn't count.
+    let misses: Vec<u32> = (1..=100).filter(|&n| n != secret).take(7).collect();
+    let mut input = String::from("abc\n");
+    for guess in &misses {
+        input.push_str(&format!("{}\n", guess));
+    }
+
+    let (output, won) = run(
+        "lost_round",
+        &["--seed", &SEED.to_string(), "--show-seed"],
+        &input,
+    );
+
+    let mut expected = vec![
+        String::from("Guess the number!"),
+        String::from("Difficulty: normal (between 1 and 100)"),
+        String::from("Please input your guess."),
+        String::from("That's not a number, please type a number."),
+    ];
+    for (number, guess) in misses.iter().enumerate() {
+        expected.push(String::from("Please input your guess."));
+        expected.push(format!("You guessed: {}", guess));
+        expected.push(String::from(if *guess < secret {
+            "Too small!"
+        } else {
+            "Too big!"
+        }));
+        let left = 6 - number;
+        if left > 0 {
+            expected.push(format!("Guesses left: {}", left));
+        }
+    }
+    expected.push(format!("You lose! The secret number was {}.", secret));
+    expected.push(format!("Seed: {}", SEED));
+
+    assert!(!won);
+    assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn a_won_round_ends_with_the_win_and_the_score() {
+    let secret = secret();
+    let (output, won) = run(
+        "won_round",
+        &["--seed", &SEED.to_string()],
+        &format!("{}\n", secret),
+    );
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(won);
+    assert_eq!(
+        lines[..4],
+        [
+            "Guess the number!",
+            "Difficulty: normal (between 1 and 100)",
+            "Please input your guess.",
+            &format!("You guessed: {}", secret)[..],
+        ]
+    );
+    assert_eq!(lines[4], "You win!");
+    assert!(lines[5].starts_with("tester won in 1 guess ("));
+    assert_eq!(lines.len(), 6);
+}
+
+#[test]
+fn closing_stdin_ends_the_round_as_a_loss() {
+    let (output, won) = run("closed_stdin", &["--seed", &SEED.to_string()], "");
+    assert!(!won);
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        [
+            "Guess the number!",
+            "Difficulty: normal (between 1 and 100)",
+            "Please input your guess."
+        ]
+    );
+}